@@ -0,0 +1,226 @@
+// ==========================================
+// ATAJOS GLOBALES (teclado y control remoto de presentaciones)
+// ==========================================
+// Se registran desde Rust con el plugin global-shortcut, así funcionan aunque la
+// ventana de control no tenga el foco. Los controles USB de presentaciones mandan
+// RePág/AvPág. Solo se registran mientras hay una salida abierta (proyector o salida
+// de audiencia); el resto del tiempo esas teclas quedan para las demás aplicaciones.
+// Negro, sin texto y logo se resuelven aquí mismo; avanzar y retroceder van al
+// carrusel si hay uno en reproducción y si no al panel (`shortcut-action`), que es
+// quien conoce la lista de versículos o estrofas y la de favoritos.
+use crate::pantalla::{self, ModoPantalla};
+use crate::{carrusel, guardar_config, leer_config, salidas, AppState};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use tauri::{Emitter, EventTarget, Manager, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+const CONFIG_ATAJOS: &str = "atajos";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AccionAtajo {
+    Siguiente,
+    Anterior,
+    Negro,             // Alterna la pantalla en negro
+    SinTexto,          // Alterna quitar el texto
+    Logo,              // Alterna el logo
+    SiguienteElemento, // Lo resuelve el panel: siguiente de la lista de favoritos
+}
+
+impl AccionAtajo {
+    const TODAS: [AccionAtajo; 6] = [
+        AccionAtajo::Siguiente,
+        AccionAtajo::Anterior,
+        AccionAtajo::Negro,
+        AccionAtajo::SinTexto,
+        AccionAtajo::Logo,
+        AccionAtajo::SiguienteElemento,
+    ];
+
+    fn teclas_por_defecto(self) -> Option<&'static str> {
+        match self {
+            AccionAtajo::Siguiente => Some("PageDown"),
+            AccionAtajo::Anterior => Some("PageUp"),
+            AccionAtajo::Negro => Some("F8"),
+            AccionAtajo::SinTexto => Some("F9"),
+            AccionAtajo::Logo => Some("F10"),
+            AccionAtajo::SiguienteElemento => Some("Shift+PageDown"),
+        }
+    }
+
+    fn modo_pantalla(self) -> Option<ModoPantalla> {
+        match self {
+            AccionAtajo::Negro => Some(ModoPantalla::Negro),
+            AccionAtajo::SinTexto => Some(ModoPantalla::SinTexto),
+            AccionAtajo::Logo => Some(ModoPantalla::Logo),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Atajo {
+    accion: AccionAtajo,
+    teclas: Option<String>, // None = sin atajo
+    registrado: bool,       // false sin salidas abiertas o si el sistema u otra aplicación ya usa la combinación
+}
+
+// Lo guardado manda; las acciones que no figuran usan su combinación por defecto
+fn leer_atajos(conn: &Connection) -> HashMap<AccionAtajo, Option<String>> {
+    let guardados: HashMap<AccionAtajo, Option<String>> = leer_config(conn, CONFIG_ATAJOS)
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    AccionAtajo::TODAS.iter()
+        .map(|accion| (*accion, guardados.get(accion).cloned().unwrap_or_else(|| accion.teclas_por_defecto().map(str::to_string))))
+        .collect()
+}
+
+fn interpretar(teclas: &str) -> Result<Shortcut, String> {
+    Shortcut::from_str(teclas.trim()).map_err(|e| format!("Atajo inválido '{}': {}", teclas, e))
+}
+
+fn registrar(app: &tauri::AppHandle, teclas: &str) -> Result<(), String> {
+    app.global_shortcut().register(interpretar(teclas)?).map_err(|e| format!("El sistema u otra aplicación ya usa '{}': {}", teclas, e))
+}
+
+fn listar(app: &tauri::AppHandle, atajos: &HashMap<AccionAtajo, Option<String>>) -> Vec<Atajo> {
+    AccionAtajo::TODAS.iter().map(|accion| {
+        let teclas = atajos.get(accion).cloned().flatten();
+        let registrado = teclas.as_deref().and_then(|t| interpretar(t).ok()).is_some_and(|s| app.global_shortcut().is_registered(s));
+        Atajo { accion: *accion, teclas, registrado }
+    }).collect()
+}
+
+fn registrar_todos(app: &tauri::AppHandle, atajos: &HashMap<AccionAtajo, Option<String>>) {
+    for teclas in atajos.values().flatten() {
+        if interpretar(teclas).is_ok_and(|s| app.global_shortcut().is_registered(s)) { continue; }
+        if let Err(e) = registrar(app, teclas) {
+            println!("Atajos: {}", e);
+        }
+    }
+}
+
+// Quita solo los atajos propios que estén registrados
+fn quitar_todos(app: &tauri::AppHandle, atajos: &HashMap<AccionAtajo, Option<String>>) {
+    for atajo in atajos.values().flatten().filter_map(|t| interpretar(t).ok()) {
+        if app.global_shortcut().is_registered(atajo) {
+            let _ = app.global_shortcut().unregister(atajo);
+        }
+    }
+}
+
+// `cerrando` es la ventana que se está destruyendo y ya no cuenta como abierta
+fn hay_salidas(app: &tauri::AppHandle, cerrando: Option<&str>) -> bool {
+    salidas::ventanas_de_salidas(app).iter().any(|(_, ventana)| Some(ventana.label()) != cerrando)
+}
+
+// Registra los atajos si hay alguna salida abierta y los quita si no. Se llama al abrir y al cerrar salidas.
+pub fn actualizar(app: &tauri::AppHandle, cerrando: Option<&str>) {
+    let atajos = match app.state::<AppState>().multimedia_db.lock() {
+        Ok(conn) => leer_atajos(&conn),
+        Err(_) => return,
+    };
+    if hay_salidas(app, cerrando) { registrar_todos(app, &atajos) } else { quitar_todos(app, &atajos) }
+}
+
+// Manejador del plugin: busca qué acción tiene la combinación pulsada
+pub fn al_pulsar(app: &tauri::AppHandle, atajo: &Shortcut, evento: ShortcutEvent) {
+    if evento.state != ShortcutState::Pressed { return; }
+    let Some(state) = app.try_state::<AppState>() else { return };
+    let atajos = match state.multimedia_db.lock() {
+        Ok(conn) => leer_atajos(&conn),
+        Err(_) => return,
+    };
+    let accion = atajos.into_iter()
+        .find(|(_, teclas)| teclas.as_deref().and_then(|t| interpretar(t).ok()).is_some_and(|s| s.id() == atajo.id()))
+        .map(|(accion, _)| accion);
+    if let Some(accion) = accion {
+        if let Err(e) = ejecutar(app, accion) {
+            println!("Atajo {:?}: {}", accion, e);
+        }
+    }
+}
+
+fn ejecutar(app: &tauri::AppHandle, accion: AccionAtajo) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    if let Some(modo) = accion.modo_pantalla() {
+        // Pulsar de nuevo vuelve a la pantalla normal
+        let actual = state.pantalla.lock().map_err(|e| e.to_string())?.modo;
+        let modo = if actual == modo { ModoPantalla::Normal } else { modo };
+        return pantalla::set_screen_mode(app.clone(), modo, None, state).map(|_| ());
+    }
+    let en_carrusel = match accion {
+        AccionAtajo::Siguiente => carrusel::slideshow_next(app.state()).is_ok(),
+        AccionAtajo::Anterior => carrusel::slideshow_prev(app.state()).is_ok(),
+        _ => false,
+    };
+    if !en_carrusel {
+        app.emit_to(EventTarget::webview_window("main"), "shortcut-action", accion).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// ==========================================
+// COMANDOS DE ATAJOS
+// ==========================================
+#[tauri::command]
+pub fn get_shortcuts(app: tauri::AppHandle, state: State<AppState>) -> Result<Vec<Atajo>, String> {
+    let atajos = leer_atajos(&*state.multimedia_db.lock().map_err(|e| e.to_string())?);
+    Ok(listar(&app, &atajos))
+}
+
+// Cambia la combinación de una acción (None la deja sin atajo). Con salidas abiertas
+// se registra al momento y, si no se puede, se restaura la anterior.
+#[tauri::command]
+pub fn set_shortcut(app: tauri::AppHandle, accion: AccionAtajo, teclas: Option<String>, state: State<AppState>) -> Result<Vec<Atajo>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let mut atajos = leer_atajos(&conn);
+    let nuevo = teclas.as_deref().map(str::trim).filter(|t| !t.is_empty()).map(interpretar).transpose()?;
+
+    if let Some(nuevo) = &nuevo {
+        let en_uso = atajos.iter().find(|(otra, teclas)| {
+            **otra != accion && teclas.as_deref().and_then(|t| interpretar(t).ok()).is_some_and(|s| s.id() == nuevo.id())
+        });
+        if let Some((otra, _)) = en_uso {
+            return Err(format!("Esa combinación ya la usa la acción {:?}", otra));
+        }
+    }
+
+    let anterior = atajos.get(&accion).cloned().flatten();
+    let anterior_atajo = anterior.as_deref().and_then(|t| interpretar(t).ok());
+    if anterior_atajo.is_some_and(|a| Some(a.id()) == nuevo.map(|n| n.id())) {
+        return Ok(listar(&app, &atajos));
+    }
+    if let Some(a) = anterior_atajo.filter(|a| app.global_shortcut().is_registered(*a)) {
+        app.global_shortcut().unregister(a).map_err(|e| e.to_string())?;
+    }
+    let teclas = nuevo.map(|n| n.to_string());
+    if let Some(t) = teclas.as_ref().filter(|_| hay_salidas(&app, None)) {
+        if let Err(e) = registrar(&app, t) {
+            if let Some(a) = &anterior { let _ = registrar(&app, a); }
+            return Err(e);
+        }
+    }
+
+    atajos.insert(accion, teclas);
+    guardar_config(&conn, CONFIG_ATAJOS, &serde_json::to_string(&atajos).map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+    Ok(listar(&app, &atajos))
+}
+
+// Vuelve a las combinaciones por defecto
+#[tauri::command]
+pub fn reset_shortcuts(app: tauri::AppHandle, state: State<AppState>) -> Result<Vec<Atajo>, String> {
+    let atajos = {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        quitar_todos(&app, &leer_atajos(&conn));
+        conn.execute("DELETE FROM configuracion WHERE clave = ?", [CONFIG_ATAJOS]).map_err(|e| e.to_string())?;
+        leer_atajos(&conn)
+    };
+    if hay_salidas(&app, None) {
+        registrar_todos(&app, &atajos);
+    }
+    Ok(listar(&app, &atajos))
+}
@@ -0,0 +1,211 @@
+// ==========================================
+// AUDIO: BIBLIOTECA, LISTAS DE REPRODUCCIÓN Y CONTROL
+// ==========================================
+use crate::biblioteca::{self, InfoBiblioteca, MediaFiltro, MediaTipo};
+use crate::protocolo::{self, EventoProyeccion};
+use crate::{ffmpeg, leer_config, AppState};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, State};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Audio { id: i32, nombre: String, ruta: String, duracion: Option<f64>, #[serde(flatten)] biblioteca: InfoBiblioteca }
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Playlist {
+    id: i32,
+    nombre: String,
+    aleatorio: bool,
+    repetir: bool,
+    crossfade: f64,  // Segundos de fundido entre pistas (0 = corte)
+}
+
+// Protocolo hacia el reproductor de audio (sigue sonando aunque cambien las diapositivas)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "accion", rename_all = "snake_case")]
+pub enum AudioControl {
+    Load { pistas: Vec<Audio>, aleatorio: bool, repetir: bool, crossfade: f64 },
+    Play,
+    Pause,
+    Stop,
+    Next,
+    Prev,
+    Seek { segundos: f64 },
+    Volume { nivel: f64 },   // 0.0 - 1.0
+    Mute { activo: bool },
+    FadeOut { segundos: f64 },
+}
+
+impl AudioControl {
+    fn validar(&self) -> Result<(), String> {
+        match self {
+            AudioControl::Volume { nivel } if !(0.0..=1.0).contains(nivel) => Err("El volumen debe estar entre 0 y 1".to_string()),
+            AudioControl::Seek { segundos } | AudioControl::FadeOut { segundos } if !segundos.is_finite() || *segundos < 0.0 => Err("El tiempo indicado no es válido".to_string()),
+            AudioControl::Load { crossfade, .. } if !(0.0..=30.0).contains(crossfade) => Err("El fundido debe estar entre 0 y 30 segundos".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub fn crear_tablas(conn: &Connection) {
+    conn.execute("CREATE TABLE IF NOT EXISTS audios (id INTEGER PRIMARY KEY AUTOINCREMENT, nombre TEXT NOT NULL, ruta TEXT NOT NULL, duracion REAL)", []).unwrap();
+    conn.execute("CREATE TABLE IF NOT EXISTS playlists (id INTEGER PRIMARY KEY AUTOINCREMENT, nombre TEXT NOT NULL, aleatorio INTEGER DEFAULT 0, repetir INTEGER DEFAULT 1, crossfade REAL DEFAULT 3)", []).unwrap();
+    conn.execute("CREATE TABLE IF NOT EXISTS playlist_audios (playlist_id INTEGER NOT NULL, audio_id INTEGER NOT NULL, orden INTEGER NOT NULL)", []).unwrap();
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_playlist_audios ON playlist_audios(playlist_id, orden)", []).unwrap();
+}
+
+fn audio_cols() -> String {
+    format!("audios.id, audios.nombre, audios.ruta, audios.duracion, {}", biblioteca::columnas_biblioteca(MediaTipo::Audio))
+}
+
+fn read_audio(row: &rusqlite::Row) -> rusqlite::Result<Audio> {
+    Ok(Audio { id: row.get(0)?, nombre: row.get(1)?, ruta: row.get(2)?, duracion: row.get(3)?, biblioteca: biblioteca::leer_info(row, 4)? })
+}
+
+fn read_playlist(row: &rusqlite::Row) -> rusqlite::Result<Playlist> {
+    Ok(Playlist { id: row.get(0)?, nombre: row.get(1)?, aleatorio: row.get::<_, i32>(2)? != 0, repetir: row.get::<_, i32>(3)? != 0, crossfade: row.get(4)? })
+}
+
+// El reproductor vive en la ventana del proyector (es la que sale por el sonido de la sala)
+fn emitir_control(app: &tauri::AppHandle, control: &AudioControl) -> Result<(), String> {
+    let projector_window = app.get_webview_window("projector").ok_or("Abra el proyector para reproducir audio")?;
+    protocolo::emitir(&projector_window, &EventoProyeccion::Audio(control.clone()));
+    Ok(())
+}
+
+// --- BIBLIOTECA ---
+#[tauri::command]
+pub fn get_all_audios(filtro: Option<MediaFiltro>, state: State<AppState>) -> Result<Vec<Audio>, String> {
+    let (clausulas, valores) = biblioteca::clausulas(MediaTipo::Audio, &filtro.unwrap_or_default(), "nombre")?;
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM audios{}", audio_cols(), clausulas)).map_err(|e| e.to_string())?;
+    let iter = stmt.query_map(rusqlite::params_from_iter(valores), read_audio).map_err(|e| e.to_string())?;
+    Ok(iter.filter_map(Result::ok).collect())
+}
+
+#[tauri::command]
+pub async fn add_audio_db(nombre: String, ruta: String, state: State<'_, AppState>) -> Result<(), String> {
+    // La duración es opcional: solo si ffprobe está instalado
+    let ffprobe = {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        ffmpeg::buscar_binario("ffprobe", leer_config(&conn, ffmpeg::CONFIG_FFPROBE))
+    };
+    let duracion = ffprobe.and_then(|f| ffmpeg::probar_video(&f, &ruta).ok()).and_then(|p| p.duracion);
+
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("INSERT INTO audios (nombre, ruta, duracion) VALUES (?, ?, ?)", params![nombre, ruta, duracion]).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_audio_db(id: i32, state: State<AppState>) -> Result<(), String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM playlist_audios WHERE audio_id = ?", params![id]).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM audios WHERE id = ?", params![id]).map_err(|e| e.to_string())?;
+    biblioteca::borrar_etiquetas(&conn, MediaTipo::Audio, id).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn select_audio_file(app: tauri::AppHandle) -> Option<Vec<String>> {
+    use tauri_plugin_dialog::DialogExt;
+    let file_paths = app.dialog()
+        .file()
+        .add_filter("Audio", &["mp3", "ogg", "flac", "wav"])
+        .blocking_pick_files();
+    file_paths.map(|paths| paths.into_iter().map(|p| p.to_string()).collect())
+}
+
+// --- LISTAS DE REPRODUCCIÓN ---
+#[tauri::command]
+pub fn get_all_playlists(state: State<AppState>) -> Result<Vec<Playlist>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare("SELECT id, nombre, COALESCE(aleatorio, 0), COALESCE(repetir, 1), COALESCE(crossfade, 3) FROM playlists ORDER BY nombre").map_err(|e| e.to_string())?;
+    let iter = stmt.query_map([], read_playlist).map_err(|e| e.to_string())?;
+    Ok(iter.filter_map(Result::ok).collect())
+}
+
+#[tauri::command]
+pub fn add_playlist(nombre: String, state: State<AppState>) -> Result<i64, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("INSERT INTO playlists (nombre) VALUES (?)", params![nombre]).map_err(|e| e.to_string())?;
+    Ok(conn.last_insert_rowid())
+}
+
+#[tauri::command]
+pub fn update_playlist(playlist: Playlist, state: State<AppState>) -> Result<(), String> {
+    if !(0.0..=30.0).contains(&playlist.crossfade) {
+        return Err("El fundido debe estar entre 0 y 30 segundos".to_string());
+    }
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE playlists SET nombre = ?, aleatorio = ?, repetir = ?, crossfade = ? WHERE id = ?",
+        params![playlist.nombre, playlist.aleatorio as i32, playlist.repetir as i32, playlist.crossfade, playlist.id]
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_playlist(id: i32, state: State<AppState>) -> Result<(), String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM playlist_audios WHERE playlist_id = ?", params![id]).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM playlists WHERE id = ?", params![id]).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn pistas_playlist(conn: &Connection, playlist_id: i32) -> rusqlite::Result<Vec<Audio>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM playlist_audios pa JOIN audios ON audios.id = pa.audio_id WHERE pa.playlist_id = ? ORDER BY pa.orden", audio_cols()))?;
+    let iter = stmt.query_map(params![playlist_id], read_audio)?;
+    Ok(iter.filter_map(Result::ok).collect())
+}
+
+#[tauri::command]
+pub fn get_playlist_audios(id: i32, state: State<AppState>) -> Result<Vec<Audio>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    pistas_playlist(&conn, id).map_err(|e| e.to_string())
+}
+
+// Reemplaza el contenido de la lista con los audios en el orden dado
+#[tauri::command]
+pub fn set_playlist_audios(id: i32, audio_ids: Vec<i32>, state: State<AppState>) -> Result<(), String> {
+    let mut conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM playlist_audios WHERE playlist_id = ?", params![id]).map_err(|e| e.to_string())?;
+    for (orden, audio_id) in audio_ids.iter().enumerate() {
+        tx.execute("INSERT INTO playlist_audios (playlist_id, audio_id, orden) VALUES (?, ?, ?)", params![id, audio_id, orden as i32 + 1]).map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// --- REPRODUCCIÓN ---
+#[tauri::command]
+pub fn play_playlist(app: tauri::AppHandle, id: i32, state: State<AppState>) -> Result<(), String> {
+    let (playlist, pistas) = {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        let playlist = conn.query_row("SELECT id, nombre, COALESCE(aleatorio, 0), COALESCE(repetir, 1), COALESCE(crossfade, 3) FROM playlists WHERE id = ?", params![id], read_playlist).map_err(|e| e.to_string())?;
+        (playlist, pistas_playlist(&conn, id).map_err(|e| e.to_string())?)
+    };
+    if pistas.is_empty() {
+        return Err("La lista de reproducción está vacía".to_string());
+    }
+
+    emitir_control(&app, &AudioControl::Load { pistas, aleatorio: playlist.aleatorio, repetir: playlist.repetir, crossfade: playlist.crossfade })?;
+    emitir_control(&app, &AudioControl::Play)
+}
+
+#[tauri::command]
+pub fn play_audio(app: tauri::AppHandle, id: i32, state: State<AppState>) -> Result<(), String> {
+    let audio = {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        conn.query_row(&format!("SELECT {} FROM audios WHERE id = ?", audio_cols()), params![id], read_audio).map_err(|e| e.to_string())?
+    };
+    emitir_control(&app, &AudioControl::Load { pistas: vec![audio], aleatorio: false, repetir: false, crossfade: 0.0 })?;
+    emitir_control(&app, &AudioControl::Play)
+}
+
+#[tauri::command]
+pub fn trigger_audio_control(app: tauri::AppHandle, action: AudioControl) -> Result<(), String> {
+    action.validar()?;
+    emitir_control(&app, &action)
+}
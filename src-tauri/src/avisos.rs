@@ -0,0 +1,368 @@
+// ==========================================
+// AVISOS (llamados de cuna, emergencias y cinta de anuncios)
+// ==========================================
+// Capa propia encima de la proyección: llega al proyector por `update-aviso`, sin
+// tocar `update-proyeccion`, así la letra sigue en pantalla. Se muestra un aviso a
+// la vez; el resto espera en cola y el backend los retira cuando vence su tiempo.
+use crate::protocolo::{self, EventoProyeccion};
+use crate::salidas::{self, Capa};
+use crate::{parse_color_hex, AppState};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+use tauri::{Emitter, Manager, State};
+
+const DURACION_POR_DEFECTO: f64 = 15.0;
+const DURACION_MAXIMA: f64 = 3600.0;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ModoAviso {
+    #[default]
+    Fijo,  // Franja con el texto quieto
+    Cinta, // El texto se desplaza de derecha a izquierda
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PosicionAviso {
+    Arriba,
+    #[default]
+    Abajo,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct EstiloAviso {
+    pub color: String,
+    pub fondo: String,
+    pub posicion: PosicionAviso,
+    pub modo: ModoAviso,
+    pub velocidad: f32, // Píxeles por segundo de la cinta
+}
+
+impl Default for EstiloAviso {
+    fn default() -> Self {
+        EstiloAviso { color: "#ffffff".to_string(), fondo: "#b91c1c".to_string(), posicion: PosicionAviso::Abajo, modo: ModoAviso::Fijo, velocidad: 120.0 }
+    }
+}
+
+impl EstiloAviso {
+    fn validar(&self) -> Result<(), String> {
+        parse_color_hex(&self.color)?;
+        parse_color_hex(&self.fondo)?;
+        if !(20.0..=600.0).contains(&self.velocidad) {
+            return Err("La velocidad de la cinta debe estar entre 20 y 600".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Aviso {
+    pub(crate) id: u64,
+    pub(crate) texto: String,
+    pub(crate) duracion: f64, // Segundos en pantalla (0 = hasta quitarlo a mano)
+    pub(crate) estilo: EstiloAviso,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct ColaAvisos {
+    actual: Option<Aviso>,
+    pendientes: VecDeque<Aviso>,
+    #[serde(skip)]
+    siguiente_id: u64,
+    // Cuenta cada cambio del aviso en pantalla. Los vencimientos lo usan en lugar del id:
+    // un aviso desplazado por uno urgente vuelve a mostrarse con otro turno, y el
+    // vencimiento de su primera aparición ya no lo retira.
+    #[serde(skip)]
+    turno: u64,
+}
+
+impl ColaAvisos {
+    // Agrega el aviso y devuelve su id, y si hay que pasar al siguiente para mostrarlo.
+    // Un aviso urgente pasa delante de todos; el que estaba en pantalla vuelve a la cola justo después.
+    fn agregar(&mut self, texto: String, duracion: f64, estilo: EstiloAviso, urgente: bool) -> (u64, bool) {
+        self.siguiente_id += 1;
+        let aviso = Aviso { id: self.siguiente_id, texto, duracion, estilo };
+        if urgente {
+            if let Some(actual) = self.actual.take() { self.pendientes.push_front(actual); }
+            self.pendientes.push_front(aviso);
+        } else {
+            self.pendientes.push_back(aviso);
+        }
+        (self.siguiente_id, self.actual.is_none())
+    }
+
+    // Quita el aviso actual y muestra el siguiente de la cola. Con `solo_si` (lo usan los
+    // vencimientos) no hace nada si el turno ya cambió. Devuelve si hubo cambio.
+    fn pasar(&mut self, solo_si: Option<u64>) -> bool {
+        if solo_si.is_some_and(|turno| turno != self.turno) {
+            return false;
+        }
+        self.actual = self.pendientes.pop_front();
+        self.turno += 1;
+        true
+    }
+
+    fn vaciar(&mut self) {
+        self.actual = None;
+        self.pendientes.clear();
+        self.turno += 1;
+    }
+}
+
+// Texto con `{valor}` para completar al mostrarla (ej: "Padres de niño #{valor}")
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PlantillaAviso {
+    pub id: Option<i32>, // None al crearla
+    pub nombre: String,
+    pub texto: String,
+    pub duracion: f64,
+    pub estilo: EstiloAviso,
+}
+
+pub fn crear_tablas(conn: &Connection) {
+    conn.execute("CREATE TABLE IF NOT EXISTS avisos_plantillas (id INTEGER PRIMARY KEY AUTOINCREMENT, nombre TEXT NOT NULL, texto TEXT NOT NULL, duracion REAL DEFAULT 15, estilo TEXT)", []).unwrap();
+}
+
+fn validar_duracion(duracion: f64) -> Result<(), String> {
+    if !duracion.is_finite() || !(0.0..=DURACION_MAXIMA).contains(&duracion) {
+        return Err("La duración del aviso debe estar entre 0 y 3600 segundos".to_string());
+    }
+    Ok(())
+}
+
+fn read_plantilla(row: &rusqlite::Row) -> rusqlite::Result<PlantillaAviso> {
+    let estilo: Option<String> = row.get(4)?;
+    Ok(PlantillaAviso {
+        id: row.get(0)?,
+        nombre: row.get(1)?,
+        texto: row.get(2)?,
+        duracion: row.get(3)?,
+        estilo: estilo.and_then(|j| serde_json::from_str(&j).ok()).unwrap_or_default(),
+    })
+}
+
+// --- COLA Y TEMPORIZADORES ---
+// El aviso va al proyector y a las salidas con la capa de avisos; el estado de la cola, al panel
+fn emitir(app: &tauri::AppHandle, aviso: Option<&Aviso>, cola: &ColaAvisos) {
+    let evento = EventoProyeccion::Aviso(aviso.cloned());
+    if let Some(projector_window) = app.get_webview_window("projector") {
+        protocolo::emitir(&projector_window, &evento);
+    }
+    for ventana in salidas::ventanas_con_capa(app, Capa::Avisos) {
+        protocolo::emitir(&ventana, &evento);
+    }
+    let _ = app.emit("alerts-state", cola);
+}
+
+fn programar_vencimiento(app: &tauri::AppHandle, aviso: &Aviso, turno: u64) {
+    if aviso.duracion <= 0.0 { return; }
+    let (app, duracion) = (app.clone(), Duration::from_secs_f64(aviso.duracion));
+    std::thread::spawn(move || {
+        std::thread::sleep(duracion);
+        let _ = avanzar(&app, Some(turno));
+    });
+}
+
+// Quita el aviso actual y muestra el siguiente de la cola.
+// Con `solo_si` (lo usan los vencimientos) no hace nada si el turno ya cambió.
+fn avanzar(app: &tauri::AppHandle, solo_si: Option<u64>) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let (mostrar, turno, cola) = {
+        let mut cola = state.avisos.lock().map_err(|e| e.to_string())?;
+        if !cola.pasar(solo_si) {
+            return Ok(());
+        }
+        (cola.actual.clone(), cola.turno, cola.clone())
+    };
+    emitir(app, mostrar.as_ref(), &cola);
+    if let Some(aviso) = mostrar {
+        programar_vencimiento(app, &aviso, turno);
+    }
+    Ok(())
+}
+
+fn encolar(app: &tauri::AppHandle, texto: String, duracion: f64, estilo: EstiloAviso, urgente: bool) -> Result<u64, String> {
+    let texto = texto.trim().to_string();
+    if texto.is_empty() { return Err("El aviso no tiene texto".to_string()); }
+    validar_duracion(duracion)?;
+    estilo.validar()?;
+
+    let state = app.state::<AppState>();
+    let (id, mostrar_ya) = state.avisos.lock().map_err(|e| e.to_string())?.agregar(texto, duracion, estilo, urgente);
+    if mostrar_ya {
+        avanzar(app, None)?;
+    } else {
+        let cola = state.avisos.lock().map_err(|e| e.to_string())?.clone();
+        let _ = app.emit("alerts-state", &cola);
+    }
+    Ok(id)
+}
+
+// ==========================================
+// COMANDOS DE AVISOS
+// ==========================================
+#[tauri::command]
+pub fn show_alert(app: tauri::AppHandle, texto: String, duracion: Option<f64>, estilo: Option<EstiloAviso>, urgente: Option<bool>) -> Result<u64, String> {
+    encolar(&app, texto, duracion.unwrap_or(DURACION_POR_DEFECTO), estilo.unwrap_or_default(), urgente.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn show_alert_template(app: tauri::AppHandle, plantilla_id: i32, valor: Option<String>, urgente: Option<bool>, state: State<AppState>) -> Result<u64, String> {
+    let plantilla = {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        conn.query_row("SELECT id, nombre, texto, duracion, estilo FROM avisos_plantillas WHERE id = ?", params![plantilla_id], read_plantilla)
+            .map_err(|_| "La plantilla no existe".to_string())?
+    };
+    let texto = plantilla.texto.replace("{valor}", valor.as_deref().unwrap_or("").trim());
+    encolar(&app, texto, plantilla.duracion, plantilla.estilo, urgente.unwrap_or(false))
+}
+
+// Quita el aviso en pantalla y pasa al siguiente
+#[tauri::command]
+pub fn dismiss_alert(app: tauri::AppHandle) -> Result<(), String> {
+    avanzar(&app, None)
+}
+
+// Retira un aviso de la cola (o de la pantalla, si es el actual)
+#[tauri::command]
+pub fn cancel_alert(app: tauri::AppHandle, id: u64, state: State<AppState>) -> Result<(), String> {
+    let cola = {
+        let mut cola = state.avisos.lock().map_err(|e| e.to_string())?;
+        if cola.actual.as_ref().is_some_and(|a| a.id == id) {
+            drop(cola);
+            return avanzar(&app, None);
+        }
+        cola.pendientes.retain(|a| a.id != id);
+        cola.clone()
+    };
+    let _ = app.emit("alerts-state", &cola);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn clear_alerts(app: tauri::AppHandle, state: State<AppState>) -> Result<(), String> {
+    let cola = {
+        let mut cola = state.avisos.lock().map_err(|e| e.to_string())?;
+        cola.vaciar();
+        cola.clone()
+    };
+    emitir(&app, None, &cola);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_alerts_state(state: State<AppState>) -> Result<ColaAvisos, String> {
+    Ok(state.avisos.lock().map_err(|e| e.to_string())?.clone())
+}
+
+// --- PLANTILLAS ---
+#[tauri::command]
+pub fn get_alert_templates(state: State<AppState>) -> Result<Vec<PlantillaAviso>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare("SELECT id, nombre, texto, duracion, estilo FROM avisos_plantillas ORDER BY nombre").map_err(|e| e.to_string())?;
+    let iter = stmt.query_map([], read_plantilla).map_err(|e| e.to_string())?;
+    Ok(iter.filter_map(Result::ok).collect())
+}
+
+#[tauri::command]
+pub fn save_alert_template(plantilla: PlantillaAviso, state: State<AppState>) -> Result<i32, String> {
+    if plantilla.nombre.trim().is_empty() || plantilla.texto.trim().is_empty() {
+        return Err("La plantilla necesita nombre y texto".to_string());
+    }
+    validar_duracion(plantilla.duracion)?;
+    plantilla.estilo.validar()?;
+    let estilo = serde_json::to_string(&plantilla.estilo).map_err(|e| e.to_string())?;
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    match plantilla.id {
+        Some(id) => {
+            conn.execute("UPDATE avisos_plantillas SET nombre = ?, texto = ?, duracion = ?, estilo = ? WHERE id = ?", params![plantilla.nombre.trim(), plantilla.texto, plantilla.duracion, estilo, id]).map_err(|e| e.to_string())?;
+            Ok(id)
+        }
+        None => {
+            conn.execute("INSERT INTO avisos_plantillas (nombre, texto, duracion, estilo) VALUES (?, ?, ?, ?)", params![plantilla.nombre.trim(), plantilla.texto, plantilla.duracion, estilo]).map_err(|e| e.to_string())?;
+            Ok(conn.last_insert_rowid() as i32)
+        }
+    }
+}
+
+#[tauri::command]
+pub fn delete_alert_template(id: i32, state: State<AppState>) -> Result<(), String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM avisos_plantillas WHERE id = ?", params![id]).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agregar(cola: &mut ColaAvisos, texto: &str, urgente: bool) -> u64 {
+        let (id, mostrar_ya) = cola.agregar(texto.to_string(), 10.0, EstiloAviso::default(), urgente);
+        if mostrar_ya { cola.pasar(None); }
+        id
+    }
+
+    fn en_pantalla(cola: &ColaAvisos) -> Option<&str> {
+        cola.actual.as_ref().map(|a| a.texto.as_str())
+    }
+
+    #[test]
+    fn respeta_el_orden_de_llegada() {
+        let mut cola = ColaAvisos::default();
+        agregar(&mut cola, "A", false);
+        agregar(&mut cola, "B", false);
+        agregar(&mut cola, "C", false);
+        assert_eq!(en_pantalla(&cola), Some("A"));
+        cola.pasar(None);
+        assert_eq!(en_pantalla(&cola), Some("B"));
+        cola.pasar(None);
+        assert_eq!(en_pantalla(&cola), Some("C"));
+        cola.pasar(None);
+        assert_eq!(en_pantalla(&cola), None);
+    }
+
+    #[test]
+    fn el_urgente_pasa_delante_y_el_desplazado_vuelve_despues() {
+        let mut cola = ColaAvisos::default();
+        agregar(&mut cola, "A", false);
+        agregar(&mut cola, "B", false);
+        agregar(&mut cola, "Urgente", true);
+        assert_eq!(en_pantalla(&cola), Some("Urgente"));
+        let pendientes: Vec<&str> = cola.pendientes.iter().map(|a| a.texto.as_str()).collect();
+        assert_eq!(pendientes, ["A", "B"]);
+    }
+
+    #[test]
+    fn el_vencimiento_viejo_no_retira_al_aviso_que_vuelve() {
+        let mut cola = ColaAvisos::default();
+        agregar(&mut cola, "A", false);
+        let turno_a = cola.turno;
+        agregar(&mut cola, "Urgente", true);
+        let turno_urgente = cola.turno;
+
+        // Vence el urgente y A vuelve a pantalla con un turno nuevo
+        assert!(cola.pasar(Some(turno_urgente)));
+        assert_eq!(en_pantalla(&cola), Some("A"));
+        // El vencimiento de la primera aparición de A llega tarde y no hace nada
+        assert!(!cola.pasar(Some(turno_a)));
+        assert_eq!(en_pantalla(&cola), Some("A"));
+        // El de su segunda aparición sí lo retira
+        assert!(cola.pasar(Some(cola.turno)));
+        assert_eq!(en_pantalla(&cola), None);
+    }
+
+    #[test]
+    fn vaciar_invalida_los_vencimientos_pendientes() {
+        let mut cola = ColaAvisos::default();
+        agregar(&mut cola, "A", false);
+        let turno_a = cola.turno;
+        cola.vaciar();
+        agregar(&mut cola, "B", false);
+        assert!(!cola.pasar(Some(turno_a)));
+        assert_eq!(en_pantalla(&cola), Some("B"));
+    }
+}
@@ -0,0 +1,276 @@
+// ==========================================
+// BIBLIOTECA MULTIMEDIA: CARPETAS, ETIQUETAS Y ORDEN
+// ==========================================
+use crate::AppState;
+use rusqlite::types::Value;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaTipo { Imagen, Video, Pdf, Audio }
+
+impl MediaTipo {
+    pub fn tabla(self) -> &'static str {
+        match self {
+            MediaTipo::Imagen => "imagenes",
+            MediaTipo::Video => "videos",
+            MediaTipo::Pdf => "pdfs",
+            MediaTipo::Audio => "audios",
+        }
+    }
+
+    pub fn desde_clave(clave: &str) -> Option<MediaTipo> {
+        [MediaTipo::Imagen, MediaTipo::Video, MediaTipo::Pdf, MediaTipo::Audio].into_iter().find(|t| t.clave() == clave)
+    }
+
+    pub fn clave(self) -> &'static str {
+        match self {
+            MediaTipo::Imagen => "imagen",
+            MediaTipo::Video => "video",
+            MediaTipo::Pdf => "pdf",
+            MediaTipo::Audio => "audio",
+        }
+    }
+}
+
+// Filtro y paginación de los comandos get_all_* (todos los campos son opcionales)
+#[derive(Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct MediaFiltro {
+    carpeta_id: Option<i32>,     // 0 = solo los elementos sin carpeta
+    etiqueta: Option<String>,
+    busqueda: Option<String>,    // Coincidencia parcial en el nombre
+    orden: Option<String>,       // "reciente" | "nombre" | "manual"
+    limite: Option<u32>,
+    offset: Option<u32>,
+}
+
+// Datos de organización comunes a todas las tablas de medios
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct InfoBiblioteca {
+    pub carpeta_id: Option<i32>,
+    pub etiquetas: Vec<String>,
+    pub faltante: bool,   // El archivo ya no existe en disco (ver carpetas vigiladas)
+}
+
+#[derive(Serialize)]
+pub struct Carpeta { id: i32, nombre: String, tipo: MediaTipo, padre_id: Option<i32>, orden: i32 }
+
+#[derive(Serialize)]
+pub struct Etiqueta { id: i32, nombre: String, usos: i32 }
+
+pub fn crear_tablas(conn: &Connection) {
+    conn.execute("CREATE TABLE IF NOT EXISTS carpetas (id INTEGER PRIMARY KEY AUTOINCREMENT, nombre TEXT NOT NULL, tipo TEXT NOT NULL, padre_id INTEGER, orden INTEGER DEFAULT 0)", []).unwrap();
+    conn.execute("CREATE TABLE IF NOT EXISTS etiquetas (id INTEGER PRIMARY KEY AUTOINCREMENT, nombre TEXT NOT NULL UNIQUE COLLATE NOCASE)", []).unwrap();
+    conn.execute("CREATE TABLE IF NOT EXISTS media_etiquetas (tipo TEXT NOT NULL, media_id INTEGER NOT NULL, etiqueta_id INTEGER NOT NULL, PRIMARY KEY (tipo, media_id, etiqueta_id))", []).unwrap();
+
+    // Cada tabla de medios guarda su carpeta y su posición manual
+    for tipo in [MediaTipo::Imagen, MediaTipo::Video, MediaTipo::Pdf, MediaTipo::Audio] {
+        let _ = conn.execute(&format!("ALTER TABLE {} ADD COLUMN carpeta_id INTEGER", tipo.tabla()), []);
+        let _ = conn.execute(&format!("ALTER TABLE {} ADD COLUMN orden INTEGER", tipo.tabla()), []);
+        let _ = conn.execute(&format!("ALTER TABLE {} ADD COLUMN faltante INTEGER DEFAULT 0", tipo.tabla()), []);
+    }
+}
+
+// Columnas extra para los SELECT de cada tabla (se leen con `leer_info`): carpeta, etiquetas separadas por \x1F y faltante
+pub fn columnas_biblioteca(tipo: MediaTipo) -> String {
+    format!(
+        "{tabla}.carpeta_id, (SELECT GROUP_CONCAT(e.nombre, char(31)) FROM media_etiquetas me JOIN etiquetas e ON e.id = me.etiqueta_id WHERE me.tipo = '{clave}' AND me.media_id = {tabla}.id), COALESCE({tabla}.faltante, 0)",
+        tabla = tipo.tabla(), clave = tipo.clave()
+    )
+}
+
+pub fn leer_info(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<InfoBiblioteca> {
+    let etiquetas: Option<String> = row.get(offset + 1)?;
+    Ok(InfoBiblioteca {
+        carpeta_id: row.get(offset)?,
+        etiquetas: etiquetas.map(|v| v.split('\u{1F}').map(str::to_string).collect()).unwrap_or_default(),
+        faltante: row.get::<_, i32>(offset + 2)? != 0,
+    })
+}
+
+// Construye "WHERE ... ORDER BY ... LIMIT ..." para el filtro dado, con sus parámetros
+pub fn clausulas(tipo: MediaTipo, filtro: &MediaFiltro, orden_por_defecto: &str) -> Result<(String, Vec<Value>), String> {
+    let tabla = tipo.tabla();
+    let mut condiciones = Vec::new();
+    let mut valores = Vec::new();
+
+    match filtro.carpeta_id {
+        Some(0) => condiciones.push(format!("{}.carpeta_id IS NULL", tabla)),
+        Some(id) => { condiciones.push(format!("{}.carpeta_id = ?", tabla)); valores.push(Value::Integer(id as i64)); }
+        None => {}
+    }
+    if let Some(etiqueta) = filtro.etiqueta.as_ref().filter(|e| !e.trim().is_empty()) {
+        condiciones.push(format!(
+            "{}.id IN (SELECT me.media_id FROM media_etiquetas me JOIN etiquetas e ON e.id = me.etiqueta_id WHERE me.tipo = '{}' AND e.nombre = ?)",
+            tabla, tipo.clave()
+        ));
+        valores.push(Value::Text(etiqueta.trim().to_string()));
+    }
+    if let Some(busqueda) = filtro.busqueda.as_ref().filter(|b| !b.trim().is_empty()) {
+        condiciones.push(format!("{}.nombre LIKE ?", tabla));
+        valores.push(Value::Text(format!("%{}%", busqueda.trim())));
+    }
+
+    let mut sql = String::new();
+    if !condiciones.is_empty() {
+        sql.push_str(&format!(" WHERE {}", condiciones.join(" AND ")));
+    }
+    sql.push_str(match filtro.orden.as_deref().unwrap_or(orden_por_defecto) {
+        "reciente" => " ORDER BY id DESC",
+        "nombre" => " ORDER BY nombre COLLATE NOCASE, id DESC",
+        // Los elementos sin posición manual quedan al final, los más nuevos primero
+        "manual" => " ORDER BY orden IS NULL, orden, id DESC",
+        otro => return Err(format!("Orden no soportado: {}", otro)),
+    });
+    if filtro.limite.is_some() || filtro.offset.is_some() {
+        sql.push_str(" LIMIT ? OFFSET ?");
+        valores.push(Value::Integer(filtro.limite.map(|l| l as i64).unwrap_or(-1)));
+        valores.push(Value::Integer(filtro.offset.unwrap_or(0) as i64));
+    }
+    Ok((sql, valores))
+}
+
+// Al borrar un elemento también se quitan sus etiquetas
+pub fn borrar_etiquetas(conn: &Connection, tipo: MediaTipo, media_id: i32) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM media_etiquetas WHERE tipo = ? AND media_id = ?", params![tipo.clave(), media_id])?;
+    Ok(())
+}
+
+// --- CARPETAS ---
+#[tauri::command]
+pub fn get_folders(tipo: MediaTipo, state: State<AppState>) -> Result<Vec<Carpeta>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare("SELECT id, nombre, padre_id, COALESCE(orden, 0) FROM carpetas WHERE tipo = ? ORDER BY orden, nombre COLLATE NOCASE").map_err(|e| e.to_string())?;
+    let iter = stmt.query_map(params![tipo.clave()], |row| Ok(Carpeta { id: row.get(0)?, nombre: row.get(1)?, tipo, padre_id: row.get(2)?, orden: row.get(3)? })).map_err(|e| e.to_string())?;
+    Ok(iter.filter_map(Result::ok).collect())
+}
+
+#[tauri::command]
+pub fn add_folder(tipo: MediaTipo, nombre: String, padre_id: Option<i32>, state: State<AppState>) -> Result<i64, String> {
+    let nombre = nombre.trim();
+    if nombre.is_empty() {
+        return Err("La carpeta necesita un nombre".to_string());
+    }
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("INSERT INTO carpetas (nombre, tipo, padre_id, orden) VALUES (?, ?, ?, (SELECT COALESCE(MAX(orden), 0) + 1 FROM carpetas WHERE tipo = ?))", params![nombre, tipo.clave(), padre_id, tipo.clave()]).map_err(|e| e.to_string())?;
+    Ok(conn.last_insert_rowid())
+}
+
+#[tauri::command]
+pub fn rename_folder(id: i32, nombre: String, state: State<AppState>) -> Result<(), String> {
+    let nombre = nombre.trim();
+    if nombre.is_empty() {
+        return Err("La carpeta necesita un nombre".to_string());
+    }
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("UPDATE carpetas SET nombre = ? WHERE id = ?", params![nombre, id]).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// Los elementos y subcarpetas pasan a la carpeta superior
+#[tauri::command]
+pub fn delete_folder(id: i32, state: State<AppState>) -> Result<(), String> {
+    let mut conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let (tipo, padre_id): (String, Option<i32>) = tx.query_row("SELECT tipo, padre_id FROM carpetas WHERE id = ?", params![id], |row| Ok((row.get(0)?, row.get(1)?))).map_err(|e| e.to_string())?;
+    let tipo = MediaTipo::desde_clave(&tipo).ok_or("Tipo de carpeta desconocido")?;
+
+    tx.execute(&format!("UPDATE {} SET carpeta_id = ? WHERE carpeta_id = ?", tipo.tabla()), params![padre_id, id]).map_err(|e| e.to_string())?;
+    tx.execute("UPDATE carpetas SET padre_id = ? WHERE padre_id = ?", params![padre_id, id]).map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM carpetas WHERE id = ?", params![id]).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// Mueve varios elementos a la vez (None = fuera de cualquier carpeta)
+#[tauri::command]
+pub fn move_media(tipo: MediaTipo, ids: Vec<i32>, carpeta_id: Option<i32>, state: State<AppState>) -> Result<(), String> {
+    let mut conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    if let Some(carpeta_id) = carpeta_id {
+        let tipo_carpeta: String = tx.query_row("SELECT tipo FROM carpetas WHERE id = ?", params![carpeta_id], |row| row.get(0)).map_err(|_| "La carpeta no existe".to_string())?;
+        if tipo_carpeta != tipo.clave() {
+            return Err("La carpeta pertenece a otro tipo de archivo".to_string());
+        }
+    }
+    for id in ids {
+        tx.execute(&format!("UPDATE {} SET carpeta_id = ? WHERE id = ?", tipo.tabla()), params![carpeta_id, id]).map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// Guarda el orden manual: la posición en la lista es el nuevo orden
+#[tauri::command]
+pub fn reorder_media(tipo: MediaTipo, ids: Vec<i32>, state: State<AppState>) -> Result<(), String> {
+    let mut conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for (orden, id) in ids.iter().enumerate() {
+        tx.execute(&format!("UPDATE {} SET orden = ? WHERE id = ?", tipo.tabla()), params![orden as i32 + 1, id]).map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn rename_media(tipo: MediaTipo, id: i32, nombre: String, state: State<AppState>) -> Result<(), String> {
+    let nombre = nombre.trim();
+    if nombre.is_empty() {
+        return Err("El nombre no puede estar vacío".to_string());
+    }
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute(&format!("UPDATE {} SET nombre = ? WHERE id = ?", tipo.tabla()), params![nombre, id]).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// --- ETIQUETAS ---
+#[tauri::command]
+pub fn get_all_tags(state: State<AppState>) -> Result<Vec<Etiqueta>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare("SELECT e.id, e.nombre, COUNT(me.media_id) FROM etiquetas e LEFT JOIN media_etiquetas me ON me.etiqueta_id = e.id GROUP BY e.id ORDER BY e.nombre COLLATE NOCASE").map_err(|e| e.to_string())?;
+    let iter = stmt.query_map([], |row| Ok(Etiqueta { id: row.get(0)?, nombre: row.get(1)?, usos: row.get(2)? })).map_err(|e| e.to_string())?;
+    Ok(iter.filter_map(Result::ok).collect())
+}
+
+// Añade la etiqueta (creándola si no existe) a varios elementos
+#[tauri::command]
+pub fn tag_media(tipo: MediaTipo, ids: Vec<i32>, etiqueta: String, state: State<AppState>) -> Result<(), String> {
+    let etiqueta = etiqueta.trim();
+    if etiqueta.is_empty() {
+        return Err("La etiqueta no puede estar vacía".to_string());
+    }
+    let mut conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute("INSERT OR IGNORE INTO etiquetas (nombre) VALUES (?)", params![etiqueta]).map_err(|e| e.to_string())?;
+    let etiqueta_id: i64 = tx.query_row("SELECT id FROM etiquetas WHERE nombre = ?", params![etiqueta], |row| row.get(0)).map_err(|e| e.to_string())?;
+    for id in ids {
+        tx.execute("INSERT OR IGNORE INTO media_etiquetas (tipo, media_id, etiqueta_id) VALUES (?, ?, ?)", params![tipo.clave(), id, etiqueta_id]).map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn untag_media(tipo: MediaTipo, ids: Vec<i32>, etiqueta: String, state: State<AppState>) -> Result<(), String> {
+    let mut conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for id in ids {
+        tx.execute(
+            "DELETE FROM media_etiquetas WHERE tipo = ? AND media_id = ? AND etiqueta_id = (SELECT id FROM etiquetas WHERE nombre = ?)",
+            params![tipo.clave(), id, etiqueta.trim()]
+        ).map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_tag(id: i32, state: State<AppState>) -> Result<(), String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM media_etiquetas WHERE etiqueta_id = ?", params![id]).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM etiquetas WHERE id = ?", params![id]).map_err(|e| e.to_string())?;
+    Ok(())
+}
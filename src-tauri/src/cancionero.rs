@@ -0,0 +1,552 @@
+// ==========================================
+// EXPORTAR CANCIONERO / HOJAS DE LETRAS EN PDF
+// ==========================================
+// Genera el PDF localmente con printpdf: portada, índice de contenido,
+// cantos con sus secciones (y acordes opcionales) e índice alfabético.
+use crate::maquetado::ancho_texto;
+use crate::AppState;
+use ab_glyph::FontArc;
+use printpdf::{Color, Greyscale, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference};
+use rusqlite::params;
+use serde::Deserialize;
+use tauri::State;
+
+const FUENTE_REGULAR: &[u8] = include_bytes!("../fonts/DejaVuSans.ttf");
+const FUENTE_NEGRITA: &[u8] = include_bytes!("../fonts/DejaVuSans-Bold.ttf");
+const MM_POR_PT: f32 = 25.4 / 72.0;
+const INTERLINEADO: f32 = 1.3;
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct CancioneroOpciones {
+    pub titulo: String,
+    pub tamano_pagina: String,   // "a4", "a5", "carta" u "oficio"
+    pub ancho_mm: Option<f32>,   // Tamaño personalizado (reemplaza a tamano_pagina)
+    pub alto_mm: Option<f32>,
+    pub margen_mm: f32,
+    pub fuente: Option<String>,         // Ruta a un .ttf/.otf; sin valor se usa DejaVu Sans
+    pub fuente_negrita: Option<String>,
+    pub tamano_letra: f32,       // Puntos
+    pub acordes: bool,           // Mostrar los acordes escritos como [G] en la letra
+    pub indices: bool,           // Índice de contenido al inicio y alfabético al final
+    pub canto_por_pagina: bool,  // Cada canto empieza en una página nueva
+}
+
+impl Default for CancioneroOpciones {
+    fn default() -> Self {
+        CancioneroOpciones {
+            titulo: "Cancionero".to_string(),
+            tamano_pagina: "a4".to_string(),
+            ancho_mm: None,
+            alto_mm: None,
+            margen_mm: 18.0,
+            fuente: None,
+            fuente_negrita: None,
+            tamano_letra: 11.0,
+            acordes: false,
+            indices: true,
+            canto_por_pagina: true,
+        }
+    }
+}
+
+impl CancioneroOpciones {
+    fn tamano(&self) -> Result<(f32, f32), String> {
+        if let (Some(ancho), Some(alto)) = (self.ancho_mm, self.alto_mm) {
+            if !(50.0..=1000.0).contains(&ancho) || !(50.0..=1000.0).contains(&alto) {
+                return Err("El tamaño de página debe estar entre 50 y 1000 mm".to_string());
+            }
+            return Ok((ancho, alto));
+        }
+        match self.tamano_pagina.as_str() {
+            "a4" => Ok((210.0, 297.0)),
+            "a5" => Ok((148.0, 210.0)),
+            "carta" => Ok((215.9, 279.4)),
+            "oficio" => Ok((215.9, 355.6)),
+            otro => Err(format!("Tamaño de página desconocido: {}", otro)),
+        }
+    }
+}
+
+struct CantoPdf {
+    titulo: String,
+    tono: String,
+    categoria: String,
+    estrofas: Vec<String>,
+}
+
+// Un texto colocado en la página (y = línea base, medida desde arriba)
+struct Texto {
+    x: f32,
+    y: f32,
+    texto: String,
+    tamano: f32,
+    negrita: bool,
+    gris: bool,
+}
+
+type Pagina = Vec<Texto>;
+
+struct Fuentes {
+    regular: FontArc,
+    negrita: FontArc,
+    datos_regular: Vec<u8>,
+    datos_negrita: Vec<u8>,
+}
+
+impl Fuentes {
+    fn cargar(opciones: &CancioneroOpciones) -> Result<Self, String> {
+        let leer = |ruta: &Option<String>, incluida: &[u8]| -> Result<Vec<u8>, String> {
+            match ruta.as_deref().filter(|r| !r.trim().is_empty()) {
+                Some(r) => std::fs::read(r).map_err(|e| format!("No se pudo leer la fuente {}: {}", r, e)),
+                None => Ok(incluida.to_vec()),
+            }
+        };
+        let datos_regular = leer(&opciones.fuente, FUENTE_REGULAR)?;
+        let datos_negrita = leer(&opciones.fuente_negrita, FUENTE_NEGRITA)?;
+        let font = |datos: &Vec<u8>| FontArc::try_from_vec(datos.clone()).map_err(|_| "La fuente no es un archivo TrueType/OpenType válido".to_string());
+        Ok(Fuentes { regular: font(&datos_regular)?, negrita: font(&datos_negrita)?, datos_regular, datos_negrita })
+    }
+
+    // Ancho en mm de un texto a `tamano` puntos
+    fn ancho(&self, texto: &str, tamano: f32, negrita: bool) -> f32 {
+        ancho_texto(if negrita { &self.negrita } else { &self.regular }, tamano, texto) * MM_POR_PT
+    }
+}
+
+// Separa "[G]Sublime [D]gracia" en la letra y los acordes con su posición (índice de carácter)
+fn separar_acordes(linea: &str) -> (String, Vec<(usize, String)>) {
+    let mut letra = String::new();
+    let mut acordes = Vec::new();
+    let mut resto = linea;
+    while let Some(inicio) = resto.find('[') {
+        let Some(fin) = resto[inicio..].find(']') else { break };
+        letra.push_str(&resto[..inicio]);
+        acordes.push((letra.chars().count(), resto[inicio + 1..inicio + fin].trim().to_string()));
+        resto = &resto[inicio + fin + 1..];
+    }
+    letra.push_str(resto);
+    (letra, acordes)
+}
+
+// Parte un texto en líneas de `ancho_max` mm
+fn partir(fuentes: &Fuentes, texto: &str, tamano: f32, negrita: bool, ancho_max: f32) -> Vec<String> {
+    let mut lineas = Vec::new();
+    let mut actual = String::new();
+    for palabra in texto.split_whitespace() {
+        let candidata = if actual.is_empty() { palabra.to_string() } else { format!("{} {}", actual, palabra) };
+        if actual.is_empty() || fuentes.ancho(&candidata, tamano, negrita) <= ancho_max {
+            actual = candidata;
+        } else {
+            lineas.push(std::mem::replace(&mut actual, palabra.to_string()));
+        }
+    }
+    lineas.push(actual);
+    lineas
+}
+
+// Como `partir`, para una línea de letra con acordes: corta entre palabras sin tocar los espacios
+// originales y devuelve cada parte con sus acordes, con la posición relativa a esa parte
+fn partir_con_acordes(fuentes: &Fuentes, letra: &str, acordes: &[(usize, String)], tamano: f32, ancho_max: f32) -> Vec<(String, Vec<(usize, String)>)> {
+    let caracteres: Vec<char> = letra.chars().collect();
+    let texto = |desde: usize, hasta: usize| caracteres[desde..hasta].iter().collect::<String>();
+
+    // Índice de carácter donde empieza cada parte
+    let mut cortes = vec![0];
+    let mut primera_palabra: Option<usize> = None;
+    let mut i = 0;
+    while i < caracteres.len() {
+        if caracteres[i].is_whitespace() { i += 1; continue; }
+        let inicio = i;
+        while i < caracteres.len() && !caracteres[i].is_whitespace() { i += 1; }
+        match primera_palabra {
+            Some(desde) if fuentes.ancho(&texto(desde, i), tamano, false) > ancho_max => {
+                cortes.push(inicio);
+                primera_palabra = Some(inicio);
+            }
+            Some(_) => {}
+            None => primera_palabra = Some(inicio),
+        }
+    }
+
+    let mut partes: Vec<(String, Vec<(usize, String)>)> = cortes.iter().enumerate()
+        .map(|(k, desde)| (texto(*desde, cortes.get(k + 1).copied().unwrap_or(caracteres.len())).trim_end().to_string(), Vec::new()))
+        .collect();
+    for (indice, acorde) in acordes {
+        // La última parte que empieza antes del acorde (un acorde al final queda en la última)
+        let k = cortes.iter().rposition(|desde| desde <= indice).unwrap_or(0);
+        partes[k].1.push((indice - cortes[k], acorde.clone()));
+    }
+    partes
+}
+
+// Reparte el contenido en páginas a medida que se agregan bloques
+struct Maquetador<'a> {
+    fuentes: &'a Fuentes,
+    opciones: &'a CancioneroOpciones,
+    ancho_util: f32,
+    alto_util: f32,
+    paginas: Vec<Pagina>,
+    y: f32, // Altura ya ocupada en la página actual (mm desde el margen superior)
+}
+
+impl<'a> Maquetador<'a> {
+    fn nueva_pagina(&mut self) {
+        self.paginas.push(Vec::new());
+        self.y = 0.0;
+    }
+
+    // Agrega un bloque de líneas sin partirlo entre páginas (salvo que no quepa en una sola)
+    fn bloque(&mut self, lineas: Vec<Texto>, alto: f32) {
+        if self.paginas.is_empty() || (self.y + alto > self.alto_util && self.y > 0.0) {
+            self.nueva_pagina();
+        }
+        let margen = self.opciones.margen_mm;
+        // Si el bloque es más alto que una página, se corta entre líneas
+        let mut corrimiento = 0.0;
+        let mut anterior = 0.0;
+        for mut linea in lineas {
+            let hay_contenido = self.paginas.last().is_some_and(|p| !p.is_empty());
+            if self.y + linea.y - corrimiento > self.alto_util && hay_contenido && linea.y > anterior {
+                self.nueva_pagina();
+                corrimiento = anterior;
+            }
+            anterior = linea.y;
+            linea.y = margen + self.y + linea.y - corrimiento;
+            linea.x += margen;
+            self.paginas.last_mut().unwrap().push(linea);
+        }
+        self.y += alto - corrimiento;
+    }
+
+    // Líneas de texto simples, una debajo de otra (y relativa al bloque)
+    fn lineas(&self, textos: &[String], tamano: f32, negrita: bool, gris: bool) -> (Vec<Texto>, f32) {
+        let alto_linea = tamano * INTERLINEADO * MM_POR_PT;
+        let mut y = 0.0;
+        let mut lineas = Vec::new();
+        for texto in textos {
+            for parte in partir(self.fuentes, texto, tamano, negrita, self.ancho_util) {
+                y += alto_linea;
+                lineas.push(Texto { x: 0.0, y, texto: parte, tamano, negrita, gris });
+            }
+        }
+        (lineas, y)
+    }
+
+    // Devuelve la página (índice) donde quedó el título
+    fn canto(&mut self, canto: &CantoPdf) -> usize {
+        let t = self.opciones.tamano_letra;
+        if self.opciones.canto_por_pagina || self.paginas.is_empty() {
+            self.nueva_pagina();
+        } else {
+            self.y += t * 2.0 * MM_POR_PT;
+        }
+
+        let (titulo, alto) = self.lineas(std::slice::from_ref(&canto.titulo), t * 1.6, true, false);
+        self.bloque(titulo, alto);
+        let pagina_titulo = self.paginas.len() - 1;
+        let datos: Vec<String> = [("Tono", &canto.tono), ("Categoría", &canto.categoria)]
+            .iter()
+            .filter(|(_, v)| !v.trim().is_empty())
+            .map(|(k, v)| format!("{}: {}", k, v))
+            .collect();
+        if !datos.is_empty() {
+            let (meta, alto) = self.lineas(&[datos.join("   ·   ")], t * 0.85, false, true);
+            self.bloque(meta, alto);
+        }
+        self.y += t * 0.8 * MM_POR_PT;
+
+        // Una estrofa que se repite es el coro: se imprime la primera vez y luego solo se indica
+        let es_coro = |texto: &str| canto.estrofas.iter().filter(|e| e.trim() == texto.trim()).count() > 1;
+        let mut coro_impreso = false;
+        let mut numero = 0;
+        for estrofa in &canto.estrofas {
+            let coro = es_coro(estrofa);
+            let etiqueta = if coro { "Coro".to_string() } else { numero += 1; format!("Estrofa {}", numero) };
+            let (mut lineas, mut alto) = self.lineas(&[etiqueta], t * 0.8, true, true);
+            if coro && coro_impreso {
+                lineas[0].texto = "(Coro)".to_string();
+            } else {
+                let (letra, alto_letra) = self.letra(estrofa, alto);
+                lineas.extend(letra);
+                alto = alto_letra;
+                coro_impreso |= coro;
+            }
+            self.bloque(lineas, alto);
+            self.y += t * 0.6 * MM_POR_PT;
+        }
+        pagina_titulo
+    }
+
+    // Letra de una estrofa con acordes encima (si se pidieron). Devuelve la altura final.
+    fn letra(&self, estrofa: &str, y_inicial: f32) -> (Vec<Texto>, f32) {
+        let t = self.opciones.tamano_letra;
+        let alto_linea = t * INTERLINEADO * MM_POR_PT;
+        let mut y = y_inicial;
+        let mut lineas = Vec::new();
+        for linea in estrofa.lines() {
+            let (letra, acordes) = separar_acordes(linea);
+            if self.opciones.acordes && !acordes.is_empty() {
+                // Los acordes van sobre la letra, alineados con la sílaba donde aparecen.
+                // Si la línea no entra se parte y cada parte lleva encima sus acordes.
+                for (parte, acordes) in partir_con_acordes(self.fuentes, &letra, &acordes, t, self.ancho_util) {
+                    if !acordes.is_empty() {
+                        y += alto_linea * 0.9;
+                        let mut fin_anterior = 0.0_f32;
+                        for (indice, acorde) in acordes {
+                            let prefijo: String = parte.chars().take(indice).collect();
+                            let x = self.fuentes.ancho(&prefijo, t, false).max(fin_anterior);
+                            fin_anterior = x + self.fuentes.ancho(&format!("{} ", acorde), t * 0.9, true);
+                            lineas.push(Texto { x, y, texto: acorde, tamano: t * 0.9, negrita: true, gris: true });
+                        }
+                    }
+                    y += alto_linea;
+                    lineas.push(Texto { x: 0.0, y, texto: parte, tamano: t, negrita: false, gris: false });
+                }
+            } else {
+                for parte in partir(self.fuentes, &letra, t, false, self.ancho_util) {
+                    y += alto_linea;
+                    lineas.push(Texto { x: 0.0, y, texto: parte, tamano: t, negrita: false, gris: false });
+                }
+            }
+        }
+        (lineas, y)
+    }
+
+    // Lista "Título ........ 12" para los índices
+    fn entradas(&mut self, encabezado: &str, entradas: &[(Option<String>, String, usize)]) {
+        let t = self.opciones.tamano_letra;
+        self.nueva_pagina();
+        let (titulo, alto) = self.lineas(&[encabezado.to_string()], t * 1.6, true, false);
+        self.bloque(titulo, alto);
+        self.y += t * MM_POR_PT;
+        let alto_linea = t * INTERLINEADO * MM_POR_PT;
+        for (letra, titulo, pagina) in entradas {
+            if let Some(letra) = letra {
+                let (grupo, alto) = self.lineas(std::slice::from_ref(letra), t * 1.1, true, true);
+                self.y += t * 0.5 * MM_POR_PT;
+                self.bloque(grupo, alto);
+            }
+            let numero = pagina.to_string();
+            let ancho_numero = self.fuentes.ancho(&numero, t, false);
+            let ancho_punto = self.fuentes.ancho(".", t, false).max(0.1);
+            let mut titulo = titulo.clone();
+            while titulo.chars().count() > 1 && self.fuentes.ancho(&titulo, t, false) > self.ancho_util - ancho_numero - 8.0 {
+                titulo.pop();
+            }
+            let libre = self.ancho_util - self.fuentes.ancho(&titulo, t, false) - ancho_numero - 4.0;
+            let puntos = ".".repeat((libre / ancho_punto).max(0.0) as usize);
+            let linea = vec![
+                Texto { x: 0.0, y: alto_linea, texto: format!("{} {}", titulo, puntos), tamano: t, negrita: false, gris: false },
+                Texto { x: self.ancho_util - ancho_numero, y: alto_linea, texto: numero, tamano: t, negrita: false, gris: false },
+            ];
+            self.bloque(linea, alto_linea);
+        }
+    }
+}
+
+fn maquetar_cantos(fuentes: &Fuentes, opciones: &CancioneroOpciones, ancho_util: f32, alto_util: f32, cantos: &[CantoPdf]) -> (Vec<Pagina>, Vec<usize>) {
+    let mut m = Maquetador { fuentes, opciones, ancho_util, alto_util, paginas: Vec::new(), y: 0.0 };
+    let mut inicios = Vec::new();
+    for canto in cantos {
+        inicios.push(m.canto(canto));
+    }
+    (m.paginas, inicios)
+}
+
+fn construir_pdf(cantos: &[CantoPdf], opciones: &CancioneroOpciones) -> Result<Vec<u8>, String> {
+    let (ancho, alto) = opciones.tamano()?;
+    let margen = opciones.margen_mm;
+    if !(5.0..=ancho / 3.0).contains(&margen) { return Err("El margen es demasiado grande para el tamaño de página".to_string()); }
+    if !(6.0..=36.0).contains(&opciones.tamano_letra) { return Err("El tamaño de letra debe estar entre 6 y 36 puntos".to_string()); }
+    let fuentes = Fuentes::cargar(opciones)?;
+    let ancho_util = ancho - margen * 2.0;
+    let alto_util = alto - margen * 2.0 - 8.0; // Espacio para el número de página
+
+    let (paginas_cantos, inicios) = maquetar_cantos(&fuentes, opciones, ancho_util, alto_util, cantos);
+
+    // Los índices necesitan los números de página finales: se maquetan una vez para contar
+    // cuántas páginas ocupan y otra ya con los números correctos
+    let contenido = |desplazamiento: usize| -> Vec<(Option<String>, String, usize)> {
+        cantos.iter().zip(&inicios).map(|(c, p)| (None, c.titulo.clone(), p + 1 + desplazamiento)).collect()
+    };
+    let alfabetico = |desplazamiento: usize| -> Vec<(Option<String>, String, usize)> {
+        let mut lista: Vec<(String, usize)> = cantos.iter().zip(&inicios).map(|(c, p)| (c.titulo.clone(), p + 1 + desplazamiento)).collect();
+        lista.sort_by_key(|(t, _)| t.to_lowercase());
+        let mut anterior = None;
+        lista.into_iter().map(|(titulo, pagina)| {
+            let inicial = titulo.chars().next().map(|c| c.to_uppercase().to_string());
+            let grupo = if inicial != anterior { anterior = inicial.clone(); inicial } else { None };
+            (grupo, titulo, pagina)
+        }).collect()
+    };
+    let maquetar_indice = |encabezado: &str, entradas: &[(Option<String>, String, usize)]| {
+        let mut m = Maquetador { fuentes: &fuentes, opciones, ancho_util, alto_util, paginas: Vec::new(), y: 0.0 };
+        m.entradas(encabezado, entradas);
+        m.paginas
+    };
+
+    let portada = 1;
+    let (indice_inicio, indice_final) = if opciones.indices && !cantos.is_empty() {
+        let paginas_indice = maquetar_indice("Contenido", &contenido(portada)).len();
+        let desplazamiento = portada + paginas_indice;
+        (maquetar_indice("Contenido", &contenido(desplazamiento)), maquetar_indice("Índice alfabético", &alfabetico(desplazamiento)))
+    } else {
+        (Vec::new(), Vec::new())
+    };
+    let desplazamiento = portada + indice_inicio.len();
+
+    // --- Escritura del PDF ---
+    let (doc, pagina0, capa0) = PdfDocument::new(opciones.titulo.clone(), Mm(ancho), Mm(alto), "Contenido");
+    let doc = doc.with_producer("EasyWorship-rs");
+    let regular = doc.add_external_font(std::io::Cursor::new(&fuentes.datos_regular)).map_err(|e| e.to_string())?;
+    let negrita = doc.add_external_font(std::io::Cursor::new(&fuentes.datos_negrita)).map_err(|e| e.to_string())?;
+
+    // Portada
+    let capa = doc.get_page(pagina0).get_layer(capa0);
+    let tamano_titulo = opciones.tamano_letra * 2.6;
+    let x = (ancho - fuentes.ancho(&opciones.titulo, tamano_titulo, true)).max(margen * 2.0) / 2.0;
+    capa.use_text(opciones.titulo.clone(), tamano_titulo, Mm(x), Mm(alto * 0.6), &negrita);
+    let subtitulo = format!("{} cantos", cantos.len());
+    let x = (ancho - fuentes.ancho(&subtitulo, opciones.tamano_letra, false)) / 2.0;
+    capa.use_text(subtitulo, opciones.tamano_letra, Mm(x), Mm(alto * 0.6 - 12.0), &regular);
+
+    let mut indices_doc = vec![pagina0];
+    let todas = indice_inicio.into_iter().chain(paginas_cantos).chain(indice_final);
+    for (i, pagina) in todas.enumerate() {
+        let numero = i + 2;
+        let (indice, capa) = doc.add_page(Mm(ancho), Mm(alto), "Contenido");
+        indices_doc.push(indice);
+        escribir_pagina(&doc, indice, capa, &pagina, alto, &regular, &negrita);
+        let texto_numero = numero.to_string();
+        let x = (ancho - fuentes.ancho(&texto_numero, opciones.tamano_letra * 0.8, false)) / 2.0;
+        doc.get_page(indice).get_layer(capa).use_text(texto_numero, opciones.tamano_letra * 0.8, Mm(x), Mm(margen * 0.6), &regular);
+    }
+
+    // Marcadores para saltar a cada canto desde el visor
+    for (canto, inicio) in cantos.iter().zip(&inicios) {
+        doc.add_bookmark(canto.titulo.clone(), indices_doc[inicio + desplazamiento]);
+    }
+
+    doc.save_to_bytes().map_err(|e| e.to_string())
+}
+
+fn escribir_pagina(doc: &PdfDocumentReference, indice: printpdf::PdfPageIndex, capa: printpdf::PdfLayerIndex, pagina: &Pagina, alto: f32, regular: &IndirectFontRef, negrita: &IndirectFontRef) {
+    let capa = doc.get_page(indice).get_layer(capa);
+    for texto in pagina {
+        let gris = if texto.gris { 0.4 } else { 0.0 };
+        capa.set_fill_color(Color::Greyscale(Greyscale::new(gris, None)));
+        capa.use_text(texto.texto.clone(), texto.tamano, Mm(texto.x), Mm(alto - texto.y), if texto.negrita { negrita } else { regular });
+    }
+}
+
+// ==========================================
+// COMANDO
+// ==========================================
+// Exporta los cantos indicados (en ese orden) a un PDF. Sin `ruta` se pregunta dónde guardarlo.
+#[tauri::command]
+pub async fn export_songbook(app: tauri::AppHandle, cantos: Vec<i32>, opciones: Option<CancioneroOpciones>, ruta: Option<String>, state: State<'_, AppState>) -> Result<String, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    if cantos.is_empty() {
+        return Err("Seleccione al menos un canto".to_string());
+    }
+    let opciones = opciones.unwrap_or_default();
+
+    let datos: Vec<CantoPdf> = {
+        let conn = state.cantos_db.lock().map_err(|e| e.to_string())?;
+        let mut lista = Vec::new();
+        for id in &cantos {
+            let (titulo, tono, categoria) = conn
+                .query_row("SELECT titulo, COALESCE(tono, ''), COALESCE(categoria, '') FROM cantos WHERE id = ?", params![id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .map_err(|_| format!("El canto {} no existe", id))?;
+            let mut stmt = conn.prepare("SELECT texto FROM diapositivas WHERE canto_id = ? ORDER BY orden").map_err(|e| e.to_string())?;
+            let estrofas = stmt.query_map(params![id], |row| row.get(0)).map_err(|e| e.to_string())?.filter_map(Result::ok).collect();
+            lista.push(CantoPdf { titulo, tono, categoria, estrofas });
+        }
+        lista
+    };
+
+    let ruta = match ruta {
+        Some(r) => r,
+        None => match app.dialog().file().add_filter("PDF", &["pdf"]).blocking_save_file() {
+            Some(p) => p.to_string(),
+            None => return Ok("Cancelado".to_string()),
+        },
+    };
+
+    let bytes = construir_pdf(&datos, &opciones)?;
+    std::fs::write(&ruta, bytes).map_err(|e| e.to_string())?;
+    Ok(format!("Exportado exitosamente a:\n{}", ruta))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fuentes() -> Fuentes {
+        Fuentes::cargar(&CancioneroOpciones::default()).unwrap()
+    }
+
+    #[test]
+    fn separa_acordes_de_la_letra() {
+        let (letra, acordes) = separar_acordes("[G]Sublime [D]gracia");
+        assert_eq!(letra, "Sublime gracia");
+        assert_eq!(acordes, vec![(0, "G".to_string()), (8, "D".to_string())]);
+    }
+
+    #[test]
+    fn posiciones_en_caracteres_con_texto_multibyte() {
+        // "Canción " tiene 8 caracteres pero 9 bytes
+        let (letra, acordes) = separar_acordes("Canci[Em]ón [C]así ñandú[D]");
+        assert_eq!(letra, "Canción así ñandú");
+        assert_eq!(acordes, vec![(5, "Em".to_string()), (8, "C".to_string()), (17, "D".to_string())]);
+        let prefijo: String = letra.chars().take(8).collect();
+        assert_eq!(prefijo, "Canción ");
+    }
+
+    #[test]
+    fn corchete_sin_cerrar_queda_como_texto() {
+        let (letra, acordes) = separar_acordes("[G]Cristo [Am vive");
+        assert_eq!(letra, "Cristo [Am vive");
+        assert_eq!(acordes, vec![(0, "G".to_string())]);
+        let (letra, acordes) = separar_acordes("Solo ]corchete[");
+        assert_eq!(letra, "Solo ]corchete[");
+        assert!(acordes.is_empty());
+    }
+
+    #[test]
+    fn partir_respeta_el_ancho() {
+        let f = fuentes();
+        let lineas = partir(&f, "Cuán grande es Él, cuán grande es Él, mi corazón entona la canción", 11.0, false, 40.0);
+        assert!(lineas.len() > 1);
+        assert!(lineas.iter().all(|l| f.ancho(l, 11.0, false) <= 40.0 || !l.contains(' ')));
+        assert_eq!(lineas.join(" "), "Cuán grande es Él, cuán grande es Él, mi corazón entona la canción");
+    }
+
+    #[test]
+    fn lineas_con_acordes_se_parten_con_sus_acordes() {
+        let f = fuentes();
+        let (letra, acordes) = separar_acordes("[G]Cuán grande es Él, [C]cuán grande es [D]Él, mi corazón entona la can[G]ción");
+        let partes = partir_con_acordes(&f, &letra, &acordes, 11.0, 40.0);
+        assert!(partes.len() > 1);
+        assert!(partes.iter().all(|(p, _)| f.ancho(p, 11.0, false) <= 40.0 || !p.contains(' ')));
+        // Cada acorde sigue sobre la misma sílaba, ahora relativa a su parte
+        for (parte, acordes_parte) in &partes {
+            for (indice, acorde) in acordes_parte {
+                let silaba: String = parte.chars().skip(*indice).take(3).collect();
+                let esperada = match acorde.as_str() { "G" if *indice == 0 => "Cuá", "G" => "ció", "C" => "cuá", "D" => "Él,", otro => panic!("acorde inesperado {}", otro) };
+                assert_eq!(silaba, esperada);
+            }
+        }
+        assert_eq!(partes.iter().map(|(_, a)| a.len()).sum::<usize>(), 4);
+    }
+
+    #[test]
+    fn linea_corta_con_acorde_al_final_no_se_parte() {
+        let f = fuentes();
+        let (letra, acordes) = separar_acordes("Amén[G]");
+        let partes = partir_con_acordes(&f, &letra, &acordes, 11.0, 100.0);
+        assert_eq!(partes, vec![("Amén".to_string(), vec![(4, "G".to_string())])]);
+    }
+}
@@ -0,0 +1,304 @@
+// ==========================================
+// CARPETAS VIGILADAS (importación automática de medios)
+// ==========================================
+use crate::biblioteca::MediaTipo;
+use crate::{importar_imagen, importar_pdf, importar_video, AppState};
+use notify::{EventKind, RecursiveMode, Watcher};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{Emitter, Manager, State};
+
+// Tiempo sin cambios antes de importar (los archivos grandes tardan en copiarse a la carpeta).
+// Cuenta tanto los eventos como el tamaño y la fecha de modificación del archivo: mientras
+// la copia siga, el archivo no se importa por mucho que tarde.
+const ESPERA_ESTABLE: Duration = Duration::from_secs(3);
+
+// Tamaño y fecha de modificación; None si el archivo ya no está
+type Huella = Option<(u64, Option<SystemTime>)>;
+
+fn huella(ruta: &Path) -> Huella {
+    std::fs::metadata(ruta).ok().map(|m| (m.len(), m.modified().ok()))
+}
+
+// Un archivo que recibió eventos y espera a quedar estable
+struct Pendiente { ultimo_evento: Instant, huella: Huella, estable_desde: Instant }
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CarpetaVigilada {
+    id: i32,
+    ruta: String,
+    tipo: MediaTipo,
+    extensiones: Vec<String>,
+    carpeta_id: Option<i32>,   // Carpeta de la biblioteca donde se guardan los nuevos archivos
+}
+
+pub fn crear_tablas(conn: &Connection) {
+    conn.execute("CREATE TABLE IF NOT EXISTS carpetas_vigiladas (id INTEGER PRIMARY KEY AUTOINCREMENT, ruta TEXT NOT NULL, tipo TEXT NOT NULL, extensiones TEXT, carpeta_id INTEGER)", []).unwrap();
+}
+
+fn extensiones_por_defecto(tipo: MediaTipo) -> &'static [&'static str] {
+    match tipo {
+        MediaTipo::Imagen => &["png", "jpg", "jpeg", "webp", "gif"],
+        MediaTipo::Video => &["mp4", "webm", "mkv", "mov", "avi", "m4v"],
+        MediaTipo::Pdf => &["pdf"],
+        MediaTipo::Audio => &["mp3", "ogg", "flac", "wav"],
+    }
+}
+
+fn leer_carpetas(conn: &Connection) -> rusqlite::Result<Vec<CarpetaVigilada>> {
+    let mut stmt = conn.prepare("SELECT id, ruta, tipo, COALESCE(extensiones, ''), carpeta_id FROM carpetas_vigiladas ORDER BY id")?;
+    let iter = stmt.query_map([], |row| {
+        let tipo: String = row.get(2)?;
+        let extensiones: String = row.get(3)?;
+        Ok((row.get(0)?, row.get(1)?, tipo, extensiones, row.get(4)?))
+    })?;
+    Ok(iter.filter_map(Result::ok).filter_map(|(id, ruta, tipo, extensiones, carpeta_id): (i32, String, String, String, Option<i32>)| {
+        let tipo = MediaTipo::desde_clave(&tipo)?;
+        let mut extensiones: Vec<String> = extensiones.split(',').map(|e| e.trim().trim_start_matches('.').to_lowercase()).filter(|e| !e.is_empty()).collect();
+        if extensiones.is_empty() {
+            extensiones = extensiones_por_defecto(tipo).iter().map(|e| e.to_string()).collect();
+        }
+        Some(CarpetaVigilada { id, ruta, tipo, extensiones, carpeta_id })
+    }).collect())
+}
+
+fn acepta(carpeta: &CarpetaVigilada, ruta: &Path) -> bool {
+    let extension = ruta.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    ruta.starts_with(&carpeta.ruta) && carpeta.extensiones.contains(&extension)
+}
+
+// En los PDFs la columna `ruta` es la carpeta de páginas; el archivo vigilado está en `ruta_original`
+fn columna_archivo(tipo: MediaTipo) -> &'static str {
+    if tipo == MediaTipo::Pdf { "ruta_original" } else { "ruta" }
+}
+
+// Importa un archivo nuevo o lo vuelve a marcar como presente. Devuelve true si hubo cambios.
+fn procesar_archivo(app: &tauri::AppHandle, carpeta: &CarpetaVigilada, ruta: &Path) -> Result<bool, String> {
+    let state = app.state::<AppState>();
+    let ruta_str = ruta.to_string_lossy().to_string();
+    let tabla = carpeta.tipo.tabla();
+
+    if !ruta.is_file() {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        let cambios = conn.execute(&format!("UPDATE {} SET faltante = 1 WHERE {} = ? AND COALESCE(faltante, 0) = 0", tabla, columna_archivo(carpeta.tipo)), params![ruta_str]).map_err(|e| e.to_string())?;
+        return Ok(cambios > 0);
+    }
+
+    {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        let existente: Option<i32> = conn.query_row(&format!("SELECT id FROM {} WHERE {} = ?", tabla, columna_archivo(carpeta.tipo)), params![ruta_str], |row| row.get(0)).ok();
+        if let Some(id) = existente {
+            let cambios = conn.execute(&format!("UPDATE {} SET faltante = 0 WHERE id = ? AND faltante = 1", tabla), params![id]).map_err(|e| e.to_string())?;
+            return Ok(cambios > 0);
+        }
+    }
+
+    // Mismo camino que los comandos add_*_db
+    let nombre = ruta.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| ruta_str.clone());
+    let id = match carpeta.tipo {
+        MediaTipo::Imagen => importar_imagen(app, &nombre, &ruta_str, None, &state)?,
+        MediaTipo::Video => importar_video(app, &nombre, &ruta_str, &state)?.id as i64,
+        MediaTipo::Pdf => importar_pdf(app, &nombre, &ruta_str, None, &state)?,
+        MediaTipo::Audio => return Ok(false),
+    };
+    if let Some(carpeta_id) = carpeta.carpeta_id {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        conn.execute(&format!("UPDATE {} SET carpeta_id = ? WHERE id = ?", tabla), params![carpeta_id, id]).map_err(|e| e.to_string())?;
+    }
+    Ok(true)
+}
+
+// Revisa toda la carpeta: importa lo nuevo y marca como faltante lo que ya no está
+fn escanear(app: &tauri::AppHandle, carpeta: &CarpetaVigilada) -> bool {
+    let mut hubo_cambios = false;
+    let mut pendientes = vec![PathBuf::from(&carpeta.ruta)];
+    while let Some(dir) = pendientes.pop() {
+        let Ok(entradas) = std::fs::read_dir(&dir) else { continue };
+        for entrada in entradas.filter_map(Result::ok) {
+            let ruta = entrada.path();
+            if ruta.is_dir() {
+                pendientes.push(ruta);
+            } else if acepta(carpeta, &ruta) {
+                match procesar_archivo(app, carpeta, &ruta) {
+                    Ok(cambio) => hubo_cambios |= cambio,
+                    Err(e) => println!("Carpeta vigilada: no se pudo importar {}: {}", ruta.display(), e),
+                }
+            }
+        }
+    }
+
+    let state = app.state::<AppState>();
+    // Con el separador al final: "C:\Fondos" no debe abarcar "C:\Fondos viejos"
+    let prefijo = format!("{}{}", carpeta.ruta.trim_end_matches(['/', '\\']), std::path::MAIN_SEPARATOR);
+    let registrados: Vec<String> = match state.multimedia_db.lock() {
+        Ok(conn) => {
+            let sql = format!("SELECT {col} FROM {} WHERE substr({col}, 1, ?) = ? AND COALESCE(faltante, 0) = 0", carpeta.tipo.tabla(), col = columna_archivo(carpeta.tipo));
+            conn.prepare(&sql)
+                .and_then(|mut stmt| stmt.query_map(params![prefijo.chars().count() as i64, prefijo], |row| row.get(0)).map(|iter| iter.filter_map(Result::ok).collect()))
+                .unwrap_or_default()
+        }
+        Err(_) => Vec::new(),
+    };
+    for ruta in registrados.iter().map(PathBuf::from).filter(|r| !r.exists()) {
+        hubo_cambios |= procesar_archivo(app, carpeta, &ruta).unwrap_or(false);
+    }
+    hubo_cambios
+}
+
+fn notificar(app: &tauri::AppHandle, tipo: MediaTipo) {
+    let _ = app.emit("media-library-changed", serde_json::json!({ "tipo": tipo }));
+}
+
+// Arranca el vigilante en segundo plano. Se llama una vez al iniciar la aplicación.
+pub fn iniciar(app: tauri::AppHandle) {
+    let (tx, rx) = mpsc::channel::<PathBuf>();
+    let watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(evento) = res {
+            if matches!(evento.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+                for ruta in evento.paths {
+                    let _ = tx.send(ruta);
+                }
+            }
+        }
+    });
+    let mut watcher = match watcher {
+        Ok(w) => w,
+        Err(e) => { println!("No se pudo iniciar el vigilante de carpetas: {}", e); return; }
+    };
+
+    let carpetas = {
+        let state = app.state::<AppState>();
+        let conn = state.multimedia_db.lock().unwrap();
+        leer_carpetas(&conn).unwrap_or_default()
+    };
+    for carpeta in &carpetas {
+        if let Err(e) = watcher.watch(Path::new(&carpeta.ruta), RecursiveMode::Recursive) {
+            println!("No se pudo vigilar {}: {}", carpeta.ruta, e);
+        }
+    }
+    *app.state::<AppState>().vigilante.lock().unwrap() = Some(watcher);
+
+    std::thread::spawn(move || {
+        // Escaneo inicial por si hubo cambios con la aplicación cerrada
+        for carpeta in &carpetas {
+            if escanear(&app, carpeta) { notificar(&app, carpeta.tipo); }
+        }
+
+        let mut pendientes: HashMap<PathBuf, Pendiente> = HashMap::new();
+        loop {
+            // Se revisa tras cada evento y no solo en los silencios: con un flujo constante
+            // de eventos (una copia grande de muchos archivos) lo ya estable se procesa igual
+            match rx.recv_timeout(Duration::from_secs(1)) {
+                Ok(ruta) => {
+                    let ahora = Instant::now();
+                    pendientes.entry(ruta)
+                        .and_modify(|p| p.ultimo_evento = ahora)
+                        .or_insert_with_key(|ruta| Pendiente { ultimo_evento: ahora, huella: huella(ruta), estable_desde: ahora });
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+
+            // Listo = sin eventos y con el mismo tamaño y fecha durante ESPERA_ESTABLE
+            let mut listas: Vec<PathBuf> = Vec::new();
+            for (ruta, pendiente) in pendientes.iter_mut() {
+                if pendiente.ultimo_evento.elapsed() < ESPERA_ESTABLE { continue; }
+                let actual = huella(ruta);
+                if actual != pendiente.huella {
+                    pendiente.huella = actual;
+                    pendiente.estable_desde = Instant::now();
+                } else if pendiente.estable_desde.elapsed() >= ESPERA_ESTABLE {
+                    listas.push(ruta.clone());
+                }
+            }
+            if listas.is_empty() { continue; }
+
+            let carpetas = {
+                let state = app.state::<AppState>();
+                let Ok(conn) = state.multimedia_db.lock() else { continue };
+                leer_carpetas(&conn).unwrap_or_default()
+            };
+            for ruta in listas {
+                pendientes.remove(&ruta);
+                let Some(carpeta) = carpetas.iter().find(|c| acepta(c, &ruta)) else { continue };
+                match procesar_archivo(&app, carpeta, &ruta) {
+                    Ok(true) => notificar(&app, carpeta.tipo),
+                    Ok(false) => {}
+                    Err(e) => println!("Carpeta vigilada: no se pudo importar {}: {}", ruta.display(), e),
+                }
+            }
+        }
+    });
+}
+
+// --- COMANDOS ---
+#[tauri::command]
+pub fn get_watch_folders(state: State<AppState>) -> Result<Vec<CarpetaVigilada>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    leer_carpetas(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn select_watch_folder(app: tauri::AppHandle) -> Option<String> {
+    use tauri_plugin_dialog::DialogExt;
+    app.dialog().file().blocking_pick_folder().map(|path| path.to_string())
+}
+
+#[tauri::command]
+pub async fn add_watch_folder(app: tauri::AppHandle, ruta: String, tipo: MediaTipo, extensiones: Option<String>, carpeta_id: Option<i32>, state: State<'_, AppState>) -> Result<(), String> {
+    if tipo == MediaTipo::Audio {
+        return Err("Las carpetas vigiladas solo admiten imágenes, videos y PDFs".to_string());
+    }
+    if !Path::new(&ruta).is_dir() {
+        return Err(format!("La carpeta no existe: {}", ruta));
+    }
+
+    let carpeta = {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        conn.execute("INSERT INTO carpetas_vigiladas (ruta, tipo, extensiones, carpeta_id) VALUES (?, ?, ?, ?)", params![ruta, tipo.clave(), extensiones, carpeta_id]).map_err(|e| e.to_string())?;
+        let id = conn.last_insert_rowid() as i32;
+        leer_carpetas(&conn).map_err(|e| e.to_string())?.into_iter().find(|c| c.id == id).ok_or("No se pudo registrar la carpeta")?
+    };
+
+    if let Some(watcher) = state.vigilante.lock().map_err(|e| e.to_string())?.as_mut() {
+        watcher.watch(Path::new(&carpeta.ruta), RecursiveMode::Recursive).map_err(|e| e.to_string())?;
+    }
+    if escanear(&app, &carpeta) {
+        notificar(&app, carpeta.tipo);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_watch_folder(id: i32, state: State<AppState>) -> Result<(), String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let ruta: String = conn.query_row("SELECT ruta FROM carpetas_vigiladas WHERE id = ?", params![id], |row| row.get(0)).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM carpetas_vigiladas WHERE id = ?", params![id]).map_err(|e| e.to_string())?;
+
+    // Solo se deja de vigilar si ninguna otra entrada usa la misma ruta
+    let en_uso: i32 = conn.query_row("SELECT COUNT(*) FROM carpetas_vigiladas WHERE ruta = ?", params![ruta], |row| row.get(0)).map_err(|e| e.to_string())?;
+    if en_uso == 0 {
+        if let Some(watcher) = state.vigilante.lock().map_err(|e| e.to_string())?.as_mut() {
+            let _ = watcher.unwatch(Path::new(&ruta));
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn rescan_watch_folders(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let carpetas = {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        leer_carpetas(&conn).map_err(|e| e.to_string())?
+    };
+    for carpeta in &carpetas {
+        if escanear(&app, carpeta) {
+            notificar(&app, carpeta.tipo);
+        }
+    }
+    Ok(())
+}
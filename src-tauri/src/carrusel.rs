@@ -0,0 +1,279 @@
+// ==========================================
+// CARRUSEL DE ANUNCIOS (imágenes y páginas de PDF con avance automático)
+// ==========================================
+use crate::protocolo::Contenido;
+use crate::temas::EstiloParcial;
+use crate::transiciones::{Transicion, Transiciones, TipoTransicion};
+use crate::AppState;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager, State};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Carrusel {
+    id: i32,
+    nombre: String,
+    duracion: f64,              // Segundos por diapositiva (si el elemento no define la suya)
+    bucle: bool,
+    transicion: TipoTransicion, // "corte" | "fundido" | "fundido_negro"
+    duracion_transicion: f64,   // Segundos
+}
+
+// Un elemento es una imagen de la biblioteca o una página de un PDF importado
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CarruselItem {
+    imagen_id: Option<i32>,
+    pdf_id: Option<i32>,
+    pagina: Option<i32>,
+    duracion: Option<f64>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CarruselEstado { id: i32, indice: usize, total: usize, pausado: bool }
+
+pub enum ComandoCarrusel { Siguiente, Anterior, Pausar, Reanudar, Detener }
+
+// Cada reproducción tiene su número: reiniciar el mismo carrusel no confunde al hilo viejo con el nuevo
+static GENERACION: AtomicU64 = AtomicU64::new(0);
+
+struct Diapositiva { payload: serde_json::Value, cambios: EstiloParcial, duracion: f64 }
+
+pub fn crear_tablas(conn: &Connection) {
+    conn.execute("CREATE TABLE IF NOT EXISTS carruseles (id INTEGER PRIMARY KEY AUTOINCREMENT, nombre TEXT NOT NULL, duracion REAL DEFAULT 8, bucle INTEGER DEFAULT 1, transicion TEXT DEFAULT 'fundido', duracion_transicion REAL DEFAULT 1)", []).unwrap();
+    conn.execute("CREATE TABLE IF NOT EXISTS carrusel_items (carrusel_id INTEGER NOT NULL, orden INTEGER NOT NULL, imagen_id INTEGER, pdf_id INTEGER, pagina INTEGER, duracion REAL)", []).unwrap();
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_carrusel_items ON carrusel_items(carrusel_id, orden)", []).unwrap();
+}
+
+fn read_carrusel(row: &rusqlite::Row) -> rusqlite::Result<Carrusel> {
+    Ok(Carrusel { id: row.get(0)?, nombre: row.get(1)?, duracion: row.get(2)?, bucle: row.get::<_, i32>(3)? != 0, transicion: TipoTransicion::desde_texto(&row.get::<_, String>(4)?).unwrap_or_default(), duracion_transicion: row.get(5)? })
+}
+
+const CARRUSEL_COLS: &str = "id, nombre, COALESCE(duracion, 8), COALESCE(bucle, 1), COALESCE(transicion, 'fundido'), COALESCE(duracion_transicion, 1)";
+
+fn validar(carrusel: &Carrusel) -> Result<(), String> {
+    if !(1.0..=3600.0).contains(&carrusel.duracion) {
+        return Err("La duración por diapositiva debe estar entre 1 segundo y 1 hora".to_string());
+    }
+    Transicion::nueva(carrusel.transicion, carrusel.duracion_transicion).validar()
+}
+
+// --- COMANDOS DE EDICIÓN ---
+#[tauri::command]
+pub fn get_all_slideshows(state: State<AppState>) -> Result<Vec<Carrusel>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM carruseles ORDER BY nombre", CARRUSEL_COLS)).map_err(|e| e.to_string())?;
+    let iter = stmt.query_map([], read_carrusel).map_err(|e| e.to_string())?;
+    Ok(iter.filter_map(Result::ok).collect())
+}
+
+#[tauri::command]
+pub fn add_slideshow(nombre: String, state: State<AppState>) -> Result<i64, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("INSERT INTO carruseles (nombre) VALUES (?)", params![nombre]).map_err(|e| e.to_string())?;
+    Ok(conn.last_insert_rowid())
+}
+
+#[tauri::command]
+pub fn update_slideshow(carrusel: Carrusel, state: State<AppState>) -> Result<(), String> {
+    validar(&carrusel)?;
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE carruseles SET nombre = ?, duracion = ?, bucle = ?, transicion = ?, duracion_transicion = ? WHERE id = ?",
+        params![carrusel.nombre, carrusel.duracion, carrusel.bucle as i32, carrusel.transicion.como_texto(), carrusel.duracion_transicion, carrusel.id]
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_slideshow(id: i32, state: State<AppState>) -> Result<(), String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM carrusel_items WHERE carrusel_id = ?", params![id]).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM carruseles WHERE id = ?", params![id]).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_slideshow_items(id: i32, state: State<AppState>) -> Result<Vec<CarruselItem>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare("SELECT imagen_id, pdf_id, pagina, duracion FROM carrusel_items WHERE carrusel_id = ? ORDER BY orden").map_err(|e| e.to_string())?;
+    let iter = stmt.query_map(params![id], |row| Ok(CarruselItem { imagen_id: row.get(0)?, pdf_id: row.get(1)?, pagina: row.get(2)?, duracion: row.get(3)? })).map_err(|e| e.to_string())?;
+    Ok(iter.filter_map(Result::ok).collect())
+}
+
+// Reemplaza los elementos del carrusel en el orden dado
+#[tauri::command]
+pub fn set_slideshow_items(id: i32, items: Vec<CarruselItem>, state: State<AppState>) -> Result<(), String> {
+    for item in &items {
+        if !matches!((item.imagen_id, item.pdf_id, item.pagina), (Some(_), None, None) | (None, Some(_), Some(_))) {
+            return Err("Cada elemento debe ser una imagen o una página de PDF".to_string());
+        }
+        if item.duracion.is_some_and(|d| !(1.0..=3600.0).contains(&d)) {
+            return Err("La duración por diapositiva debe estar entre 1 segundo y 1 hora".to_string());
+        }
+    }
+
+    let mut conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM carrusel_items WHERE carrusel_id = ?", params![id]).map_err(|e| e.to_string())?;
+    for (orden, item) in items.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO carrusel_items (carrusel_id, orden, imagen_id, pdf_id, pagina, duracion) VALUES (?, ?, ?, ?, ?, ?)",
+            params![id, orden as i32 + 1, item.imagen_id, item.pdf_id, item.pagina, item.duracion]
+        ).map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// --- REPRODUCCIÓN ---
+// Arma los payloads de `update-proyeccion` igual que los que envía el panel de control
+fn cargar_diapositivas(conn: &Connection, carrusel: &Carrusel) -> rusqlite::Result<Vec<Diapositiva>> {
+    let mut stmt = conn.prepare(
+        "SELECT ci.duracion, COALESCE(i.ruta_proyeccion, i.ruta), COALESCE(i.aspecto, 'contain'), p.ruta, ci.pagina, COALESCE(p.formato, 'jpg')
+         FROM carrusel_items ci
+         LEFT JOIN imagenes i ON i.id = ci.imagen_id
+         LEFT JOIN pdfs p ON p.id = ci.pdf_id
+         WHERE ci.carrusel_id = ? ORDER BY ci.orden"
+    )?;
+    let iter = stmt.query_map(params![carrusel.id], |row| {
+        let duracion: Option<f64> = row.get(0)?;
+        let imagen: Option<String> = row.get(1)?;
+        let aspecto: String = row.get(2)?;
+        let pdf: Option<String> = row.get(3)?;
+        let pagina: Option<i32> = row.get(4)?;
+        let formato: String = row.get(5)?;
+        Ok((duracion, imagen, aspecto, pdf, pagina, formato))
+    })?;
+
+    // Misma especificación que el resto de la proyección: todo el cambio es de fondo y texto a la vez
+    let cambios = EstiloParcial { transiciones: Some(Transiciones::iguales(Transicion::nueva(carrusel.transicion, carrusel.duracion_transicion))), ..Default::default() };
+    Ok(iter.filter_map(Result::ok).filter_map(|(duracion, imagen, aspecto, pdf, pagina, formato)| {
+        // Los elementos cuyo archivo se borró de la biblioteca se omiten
+        let payload = match (imagen, pdf) {
+            (Some(ruta), _) => Contenido::Imagen { ruta, aspecto },
+            (None, Some(ruta)) => Contenido::Pdf { ruta, pagina: pagina.unwrap_or(1), formato },
+            (None, None) => return None,
+        };
+        Some(Diapositiva { payload: payload.a_payload().ok()?, cambios: cambios.clone(), duracion: duracion.unwrap_or(carrusel.duracion) })
+    }).collect())
+}
+
+fn emitir_estado(app: &tauri::AppHandle, estado: Option<&CarruselEstado>) {
+    let _ = app.emit("slideshow-state", estado);
+}
+
+// Hilo que avanza las diapositivas; no depende de que la ventana de control esté libre
+fn reproducir(app: tauri::AppHandle, generacion: u64, id: i32, bucle: bool, diapositivas: Vec<Diapositiva>, rx: mpsc::Receiver<ComandoCarrusel>) {
+    let total = diapositivas.len();
+    let mut indice = 0;
+    let mut pausado = false;
+
+    'mostrar: loop {
+        // Pasa por el mismo camino que el panel: queda como proyección actual (y con la
+        // pantalla congelada, preparada para cuando se descongele). Se hace con el carrusel
+        // bloqueado para que un envío del operador no quede tapado por esta diapositiva.
+        {
+            let state = app.state::<AppState>();
+            let Ok(activo) = state.carrusel.lock() else { break 'mostrar };
+            if activo.as_ref().is_none_or(|(actual, _)| *actual != generacion) { break 'mostrar; }
+            let diapositiva = &diapositivas[indice];
+            let _ = crate::proyectar(&app, &state, diapositiva.payload.clone(), Some(diapositiva.cambios.clone()));
+        }
+        emitir_estado(&app, Some(&CarruselEstado { id, indice, total, pausado }));
+
+        let mut restante = Duration::from_secs_f64(diapositivas[indice].duracion);
+        loop {
+            let inicio = Instant::now();
+            let recibido = if pausado { rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected) } else { rx.recv_timeout(restante) };
+            // El tiempo en pausa no cuenta para la diapositiva actual
+            if !pausado { restante = restante.saturating_sub(inicio.elapsed()); }
+            match recibido {
+                Ok(ComandoCarrusel::Siguiente) | Err(mpsc::RecvTimeoutError::Timeout) => {
+                    if indice + 1 < total { indice += 1; }
+                    else if bucle { indice = 0; }
+                    else { break 'mostrar; }
+                    continue 'mostrar;
+                }
+                Ok(ComandoCarrusel::Anterior) => {
+                    indice = if indice == 0 { total - 1 } else { indice - 1 };
+                    continue 'mostrar;
+                }
+                Ok(ComandoCarrusel::Pausar) => pausado = true,
+                Ok(ComandoCarrusel::Reanudar) => pausado = false,
+                Ok(ComandoCarrusel::Detener) | Err(mpsc::RecvTimeoutError::Disconnected) => break 'mostrar,
+            }
+            emitir_estado(&app, Some(&CarruselEstado { id, indice, total, pausado }));
+        }
+    }
+
+    // Solo se limpia el estado si este hilo sigue siendo el carrusel activo
+    let state = app.state::<AppState>();
+    let era_activo = match state.carrusel.lock() {
+        Ok(mut activo) if activo.as_ref().is_some_and(|(actual, _)| *actual == generacion) => { *activo = None; true }
+        _ => false,
+    };
+    if era_activo {
+        emitir_estado(&app, None);
+    }
+}
+
+#[tauri::command]
+pub fn start_slideshow(app: tauri::AppHandle, id: i32, state: State<AppState>) -> Result<(), String> {
+    let (carrusel, diapositivas) = {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        let carrusel = conn.query_row(&format!("SELECT {} FROM carruseles WHERE id = ?", CARRUSEL_COLS), params![id], read_carrusel).map_err(|e| e.to_string())?;
+        let diapositivas = cargar_diapositivas(&conn, &carrusel).map_err(|e| e.to_string())?;
+        (carrusel, diapositivas)
+    };
+    if diapositivas.is_empty() {
+        return Err("El carrusel no tiene diapositivas".to_string());
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let generacion = GENERACION.fetch_add(1, Ordering::Relaxed) + 1;
+    let anterior = state.carrusel.lock().map_err(|e| e.to_string())?.replace((generacion, tx));
+    if let Some((_, anterior)) = anterior {
+        let _ = anterior.send(ComandoCarrusel::Detener);
+    }
+    std::thread::spawn(move || reproducir(app, generacion, carrusel.id, carrusel.bucle, diapositivas, rx));
+    Ok(())
+}
+
+fn enviar(state: &AppState, comando: ComandoCarrusel) -> Result<(), String> {
+    let activo = state.carrusel.lock().map_err(|e| e.to_string())?;
+    let (_, tx) = activo.as_ref().ok_or("No hay ningún carrusel en reproducción")?;
+    tx.send(comando).map_err(|_| "El carrusel ya terminó".to_string())
+}
+
+// Detiene el carrusel activo, si lo hay (lo usa el panel al proyectar otra cosa)
+pub fn detener(app: &tauri::AppHandle, state: &AppState) -> Result<(), String> {
+    let activo = state.carrusel.lock().map_err(|e| e.to_string())?.take();
+    if let Some((_, tx)) = activo {
+        let _ = tx.send(ComandoCarrusel::Detener);
+        emitir_estado(app, None);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn stop_slideshow(state: State<AppState>) -> Result<(), String> {
+    enviar(&state, ComandoCarrusel::Detener)
+}
+
+#[tauri::command]
+pub fn slideshow_next(state: State<AppState>) -> Result<(), String> {
+    enviar(&state, ComandoCarrusel::Siguiente)
+}
+
+#[tauri::command]
+pub fn slideshow_prev(state: State<AppState>) -> Result<(), String> {
+    enviar(&state, ComandoCarrusel::Anterior)
+}
+
+#[tauri::command]
+pub fn pause_slideshow(pausar: bool, state: State<AppState>) -> Result<(), String> {
+    enviar(&state, if pausar { ComandoCarrusel::Pausar } else { ComandoCarrusel::Reanudar })
+}
@@ -0,0 +1,94 @@
+// ==========================================
+// FFPROBE / FFMPEG (opcionales, se usan si están instalados)
+// ==========================================
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub const CONFIG_FFPROBE: &str = "ffprobe_path";
+pub const CONFIG_FFMPEG: &str = "ffmpeg_path";
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct VideoProbe {
+    pub duracion: Option<f64>,   // Segundos
+    pub ancho: Option<u32>,
+    pub alto: Option<u32>,
+    pub codec: Option<String>,   // Códec de video (h264, hevc, vp9...)
+    pub tiene_audio: Option<bool>,
+}
+
+// En Windows evitamos que se abra una consola por cada proceso
+fn comando(programa: &Path) -> Command {
+    #[allow(unused_mut)]
+    let mut cmd = Command::new(programa);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+    cmd
+}
+
+// Ruta configurada o el binario del PATH, solo si realmente se puede ejecutar
+pub fn buscar_binario(nombre: &str, configurada: Option<String>) -> Option<PathBuf> {
+    let ruta = match configurada.filter(|r| !r.trim().is_empty()) {
+        Some(r) => PathBuf::from(r.trim()),
+        None => PathBuf::from(if cfg!(target_os = "windows") { format!("{}.exe", nombre) } else { nombre.to_string() }),
+    };
+    comando(&ruta).arg("-version").output().ok().filter(|o| o.status.success()).map(|_| ruta)
+}
+
+pub fn probar_video(ffprobe: &Path, ruta: &str) -> Result<VideoProbe, String> {
+    let salida = comando(ffprobe)
+        .args(["-v", "error", "-print_format", "json", "-show_format", "-show_streams"])
+        .arg(ruta)
+        .output()
+        .map_err(|e| format!("No se pudo ejecutar ffprobe: {}", e))?;
+    if !salida.status.success() {
+        return Err(format!("ffprobe no pudo leer el video: {}", String::from_utf8_lossy(&salida.stderr).trim()));
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&salida.stdout).map_err(|e| e.to_string())?;
+    let streams = json["streams"].as_array().cloned().unwrap_or_default();
+    let video = streams.iter().find(|s| s["codec_type"] == "video");
+
+    Ok(VideoProbe {
+        // ffprobe devuelve la duración como texto ("12.345000")
+        duracion: json["format"]["duration"].as_str().and_then(|d| d.parse().ok()),
+        ancho: video.and_then(|v| v["width"].as_u64()).map(|w| w as u32),
+        alto: video.and_then(|v| v["height"].as_u64()).map(|h| h as u32),
+        codec: video.and_then(|v| v["codec_name"].as_str()).map(str::to_string),
+        tiene_audio: Some(streams.iter().any(|s| s["codec_type"] == "audio")),
+    })
+}
+
+// Extrae un fotograma como miniatura (480px de ancho)
+pub fn generar_poster(ffmpeg: &Path, ruta: &str, segundo: f64, salida: &Path) -> Result<(), String> {
+    let resultado = comando(ffmpeg)
+        .args(["-y", "-v", "error", "-ss", &format!("{:.2}", segundo), "-i"])
+        .arg(ruta)
+        .args(["-frames:v", "1", "-vf", "scale=480:-2"])
+        .arg(salida)
+        .output()
+        .map_err(|e| format!("No se pudo ejecutar ffmpeg: {}", e))?;
+    if !resultado.status.success() || !salida.exists() {
+        return Err(format!("ffmpeg no pudo generar la miniatura: {}", String::from_utf8_lossy(&resultado.stderr).trim()));
+    }
+    Ok(())
+}
+
+// Avisos para formatos que WebKitGTK (Linux) o WebView2 (Windows) probablemente no reproduzcan
+pub fn advertencias_compatibilidad(ruta: &str, codec: Option<&str>) -> Vec<String> {
+    let mut advertencias = Vec::new();
+    let extension = Path::new(ruta).extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+
+    if !["mp4", "m4v", "webm", "mov"].contains(&extension.as_str()) {
+        advertencias.push(format!("El contenedor .{} puede no reproducirse en el proyector. Se recomienda MP4 o WebM.", extension));
+    }
+    match codec {
+        Some("h264") | Some("vp8") | Some("vp9") | Some("av1") | None => {}
+        Some("hevc") => advertencias.push("El códec H.265/HEVC requiere extensiones de pago en Windows y no siempre funciona en Linux. Se recomienda H.264.".to_string()),
+        Some(otro) => advertencias.push(format!("El códec {} probablemente no se reproduzca en el proyector. Se recomienda H.264 o VP9.", otro)),
+    }
+    advertencias
+}
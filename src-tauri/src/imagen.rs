@@ -0,0 +1,148 @@
+// ==========================================
+// PROCESADO DE IMÁGENES (orientación EXIF, recorte, rotación y reducción)
+// ==========================================
+// El archivo original nunca se modifica: la versión que se proyecta se genera
+// aparte en app_data/imagenes a partir del original y de estos parámetros.
+use image::{AnimationDecoder, DynamicImage, ImageDecoder, ImageReader};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// Opciones elegidas al importar
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ImagenImportOpciones {
+    pub corregir_orientacion: bool, // Aplicar la orientación EXIF (fotos de celular)
+    pub reducir: bool,              // Reducir a la resolución del proyector
+}
+
+impl Default for ImagenImportOpciones {
+    fn default() -> Self {
+        ImagenImportOpciones { corregir_orientacion: true, reducir: true }
+    }
+}
+
+// Recorte en fracciones (0.0 - 1.0) de la imagen ya orientada y rotada
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Recorte {
+    pub x: f64,
+    pub y: f64,
+    pub ancho: f64,
+    pub alto: f64,
+}
+
+// Edición no destructiva que se guarda junto al `aspecto`
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ImagenEdicion {
+    pub rotacion: u16, // 0, 90, 180 o 270 (sentido horario)
+    pub recorte: Option<Recorte>,
+}
+
+impl ImagenEdicion {
+    pub fn validar(&self) -> Result<(), String> {
+        if ![0, 90, 180, 270].contains(&self.rotacion) {
+            return Err(format!("Rotación inválida: {} (use 0, 90, 180 o 270)", self.rotacion));
+        }
+        if let Some(r) = self.recorte {
+            let dentro = |v: f64| (0.0..=1.0).contains(&v);
+            if !dentro(r.x) || !dentro(r.y) || r.ancho <= 0.0 || r.alto <= 0.0 || r.x + r.ancho > 1.0 + 1e-6 || r.y + r.alto > 1.0 + 1e-6 {
+                return Err("El recorte debe quedar dentro de la imagen".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn recorte_desde_columnas(x: Option<f64>, y: Option<f64>, ancho: Option<f64>, alto: Option<f64>) -> Option<Recorte> {
+        Some(Recorte { x: x?, y: y?, ancho: ancho?, alto: alto? })
+    }
+}
+
+// GIF o WebP con más de un cuadro. Al re-codificarlos quedaría solo el primero.
+pub fn es_animada(origen: &Path) -> bool {
+    let Ok(archivo) = std::fs::File::open(origen) else { return false };
+    let lector = std::io::BufReader::new(archivo);
+    match origen.extension().map(|e| e.to_string_lossy().to_lowercase()).as_deref() {
+        Some("gif") => image::codecs::gif::GifDecoder::new(lector).is_ok_and(|d| d.into_frames().take(2).count() > 1),
+        Some("webp") => image::codecs::webp::WebPDecoder::new(lector).is_ok_and(|d| d.has_animation()),
+        _ => false,
+    }
+}
+
+// Genera la versión para proyectar en `salida`.
+// Devuelve false si el original ya sirve tal cual (no hace falta una copia).
+pub fn procesar(origen: &Path, opciones: &ImagenImportOpciones, edicion: &ImagenEdicion, maximo: (u32, u32), salida: &Path) -> Result<bool, String> {
+    // Las animadas se proyectan siempre desde el original para no perder la animación
+    if es_animada(origen) {
+        return Ok(false);
+    }
+    let mut decoder = ImageReader::open(origen)
+        .and_then(|r| r.with_guessed_format())
+        .map_err(|e| format!("No se pudo abrir la imagen: {}", e))?
+        .into_decoder()
+        .map_err(|e| format!("Formato de imagen no soportado: {}", e))?;
+    let orientacion = if opciones.corregir_orientacion { decoder.orientation().ok() } else { None };
+    let mut imagen = DynamicImage::from_decoder(decoder).map_err(|e| format!("No se pudo leer la imagen: {}", e))?;
+    let mut modificada = false;
+
+    if let Some(orientacion) = orientacion.filter(|o| *o != image::metadata::Orientation::NoTransforms) {
+        imagen.apply_orientation(orientacion);
+        modificada = true;
+    }
+
+    imagen = match edicion.rotacion {
+        90 => imagen.rotate90(),
+        180 => imagen.rotate180(),
+        270 => imagen.rotate270(),
+        _ => imagen,
+    };
+    modificada |= edicion.rotacion != 0;
+
+    if let Some(r) = edicion.recorte {
+        let (w, h) = (imagen.width() as f64, imagen.height() as f64);
+        let x = (r.x * w).round() as u32;
+        let y = (r.y * h).round() as u32;
+        let ancho = ((r.ancho * w).round() as u32).clamp(1, imagen.width() - x.min(imagen.width() - 1));
+        let alto = ((r.alto * h).round() as u32).clamp(1, imagen.height() - y.min(imagen.height() - 1));
+        imagen = imagen.crop_imm(x, y, ancho, alto);
+        modificada = true;
+    }
+
+    // Se reduce lo justo para que siga cubriendo la pantalla en modo 'cover'
+    if opciones.reducir {
+        let escala = (maximo.0 as f64 / imagen.width() as f64).max(maximo.1 as f64 / imagen.height() as f64);
+        if escala < 1.0 {
+            let ancho = ((imagen.width() as f64 * escala).round() as u32).max(1);
+            let alto = ((imagen.height() as f64 * escala).round() as u32).max(1);
+            imagen = imagen.resize_exact(ancho, alto, image::imageops::FilterType::Lanczos3);
+            modificada = true;
+        }
+    }
+
+    if !modificada {
+        return Ok(false);
+    }
+    guardar(imagen, salida)?;
+    Ok(true)
+}
+
+// PNG si la imagen tiene transparencia, JPEG en cualquier otro caso
+pub fn extension_salida(origen: &Path) -> &'static str {
+    match origen.extension().map(|e| e.to_string_lossy().to_lowercase()).as_deref() {
+        Some("png") | Some("gif") | Some("webp") => "png",
+        _ => "jpg",
+    }
+}
+
+pub fn guardar(imagen: DynamicImage, salida: &Path) -> Result<(), String> {
+    let resultado = if salida.extension().is_some_and(|e| e == "png") {
+        imagen.save_with_format(salida, image::ImageFormat::Png)
+    } else {
+        std::fs::File::create(salida)
+            .map_err(image::ImageError::IoError)
+            .and_then(|archivo| {
+                let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(std::io::BufWriter::new(archivo), 90);
+                imagen.into_rgb8().write_with_encoder(encoder)
+            })
+    };
+    resultado.map_err(|e| format!("No se pudo guardar la imagen procesada: {}", e))
+}
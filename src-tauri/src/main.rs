@@ -3,7 +3,7 @@
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
-use tauri::{Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder};
+use tauri::{Emitter, EventTarget, Manager, State};
 use std::fs;
 use std::path::PathBuf;
 use tauri::path::BaseDirectory;
@@ -12,6 +12,29 @@ use rayon::prelude::*;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
+mod atajos;
+mod audio;
+mod avisos;
+mod cancionero;
+mod biblioteca;
+mod carpetas_vigiladas;
+mod carrusel;
+mod ffmpeg;
+mod imagen;
+mod maquetado;
+mod obs;
+mod pantalla;
+mod protocolo;
+mod renderizado;
+mod salidas;
+mod superposicion;
+mod temas;
+mod temporizadores;
+mod transiciones;
+
+use biblioteca::{InfoBiblioteca, MediaFiltro, MediaTipo};
+use protocolo::EventoProyeccion;
+
 // --- ESTRUCTURAS ---
 #[derive(Serialize, Deserialize, Clone, Debug)]
 struct Verse { libro: String, capitulo: i32, versiculo: i32, texto: String }
@@ -20,17 +43,41 @@ struct Verse { libro: String, capitulo: i32, versiculo: i32, texto: String }
 struct BookInfo { nombre: String, capitulos: i32 }
 
 #[derive(Serialize)]
-struct Canto { id: i32, titulo: String, tono: String, categoria: String }
+struct Canto { id: i32, titulo: String, tono: String, categoria: String, tema_id: Option<i32> }
 
 #[derive(Serialize)]
 struct Diapositiva { id: i32, orden: i32, texto: String }
 
 #[derive(Serialize)]
-struct Imagen { id: i32, nombre: String, ruta: String, aspecto: String }
+struct Imagen {
+    id: i32,
+    nombre: String,
+    ruta: String,          // Versión que se proyecta (procesada si hizo falta)
+    ruta_original: String, // Archivo importado, nunca se modifica
+    aspecto: String,
+    opciones: imagen::ImagenImportOpciones,
+    edicion: imagen::ImagenEdicion,
+    #[serde(flatten)]
+    biblioteca: InfoBiblioteca,
+}
 
-// NUEVA ESTRUCTURA: Video (Añadido 'bucle')
+// NUEVA ESTRUCTURA: Video (Añadido 'bucle' y los metadatos de ffprobe)
 #[derive(Serialize)]
-struct Video { id: i32, nombre: String, ruta: String, bucle: bool }
+struct Video {
+    id: i32,
+    nombre: String,
+    ruta: String,
+    bucle: bool,
+    duracion: Option<f64>,
+    ancho: Option<u32>,
+    alto: Option<u32>,
+    codec: Option<String>,
+    tiene_audio: Option<bool>,
+    poster: Option<String>,
+    advertencias: Vec<String>,
+    #[serde(flatten)]
+    biblioteca: InfoBiblioteca,
+}
 
 #[derive(Serialize, Deserialize)]
 struct CantoExport {
@@ -45,6 +92,15 @@ struct AppState {
     cantos_db: Mutex<Connection>,
     biblias_db: Mutex<Connection>,
     multimedia_db: Mutex<Connection>,
+    video_estado: Mutex<Option<VideoEstado>>,
+    vigilante: Mutex<Option<notify::RecommendedWatcher>>,
+    carrusel: Mutex<Option<(u64, std::sync::mpsc::Sender<carrusel::ComandoCarrusel>)>>, // Generación de la reproducción y su canal
+    proyeccion: Mutex<Option<(serde_json::Value, Option<temas::EstiloParcial>)>>, // Último envío del panel (sin resolver)
+    superposicion: Mutex<Option<Arc<tiny_http::Server>>>, // Servidor de la superposición para transmisión
+    avisos: Mutex<avisos::ColaAvisos>,
+    temporizadores: Mutex<temporizadores::Temporizadores>,
+    pantalla: Mutex<pantalla::EstadoPantalla>, // Negro, sin texto, logo o congelado
+    obs: Mutex<Option<std::sync::mpsc::Sender<obs::TrabajoObs>>>, // Hilo que envía las acciones a OBS en orden
 }
 
 // ==========================================
@@ -53,8 +109,8 @@ struct AppState {
 #[tauri::command]
 fn get_all_cantos(state: State<AppState>) -> Result<Vec<Canto>, String> {
     let conn = state.cantos_db.lock().map_err(|_| "Error de concurrencia")?;
-    let mut stmt = conn.prepare("SELECT id, titulo, COALESCE(tono, ''), COALESCE(categoria, '') FROM cantos ORDER BY titulo").map_err(|e| e.to_string())?;
-    let iter = stmt.query_map([], |row| { Ok(Canto { id: row.get(0)?, titulo: row.get(1)?, tono: row.get(2)?, categoria: row.get(3)? }) }).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare("SELECT id, titulo, COALESCE(tono, ''), COALESCE(categoria, ''), tema_id FROM cantos ORDER BY titulo").map_err(|e| e.to_string())?;
+    let iter = stmt.query_map([], |row| { Ok(Canto { id: row.get(0)?, titulo: row.get(1)?, tono: row.get(2)?, categoria: row.get(3)?, tema_id: row.get(4)? }) }).map_err(|e| e.to_string())?;
     Ok(iter.filter_map(Result::ok).collect())
 }
 
@@ -112,27 +168,123 @@ fn delete_canto(id: i32, state: State<AppState>) -> Result<(), String> {
 // COMANDOS DE IMÁGENES
 // ==========================================
 #[tauri::command]
-fn get_all_images(state: State<AppState>) -> Result<Vec<Imagen>, String> {
+fn get_all_images(filtro: Option<MediaFiltro>, state: State<AppState>) -> Result<Vec<Imagen>, String> {
+    let (clausulas, valores) = biblioteca::clausulas(MediaTipo::Imagen, &filtro.unwrap_or_default(), "reciente")?;
     let conn = state.multimedia_db.lock().unwrap();
-    let mut stmt = conn.prepare("SELECT id, nombre, ruta, COALESCE(aspecto, 'contain') FROM imagenes ORDER BY id DESC").unwrap();
-    let iter = stmt.query_map([], |row| Ok(Imagen { id: row.get(0)?, nombre: row.get(1)?, ruta: row.get(2)?, aspecto: row.get(3)? })).unwrap();
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM imagenes{}", imagen_cols(), clausulas)).map_err(|e| e.to_string())?;
+    let iter = stmt.query_map(rusqlite::params_from_iter(valores), read_imagen).map_err(|e| e.to_string())?;
     Ok(iter.filter_map(Result::ok).collect())
 }
 
+fn imagen_cols() -> String {
+    format!(
+        "id, nombre, COALESCE(ruta_proyeccion, ruta), ruta, COALESCE(aspecto, 'contain'), COALESCE(corregir_orientacion, 0), COALESCE(reducir, 0), COALESCE(rotacion, 0), recorte_x, recorte_y, recorte_ancho, recorte_alto, {}",
+        biblioteca::columnas_biblioteca(MediaTipo::Imagen)
+    )
+}
+
+fn read_imagen(row: &rusqlite::Row) -> rusqlite::Result<Imagen> {
+    Ok(Imagen {
+        id: row.get(0)?,
+        nombre: row.get(1)?,
+        ruta: row.get(2)?,
+        ruta_original: row.get(3)?,
+        aspecto: row.get(4)?,
+        opciones: imagen::ImagenImportOpciones { corregir_orientacion: row.get::<_, i32>(5)? != 0, reducir: row.get::<_, i32>(6)? != 0 },
+        edicion: imagen::ImagenEdicion {
+            rotacion: row.get(7)?,
+            recorte: imagen::ImagenEdicion::recorte_desde_columnas(row.get(8)?, row.get(9)?, row.get(10)?, row.get(11)?),
+        },
+        biblioteca: biblioteca::leer_info(row, 12)?,
+    })
+}
+
+// Sin opciones se corrige la orientación y se reduce al tamaño del proyector
 #[tauri::command]
-fn add_image_db(nombre: String, ruta: String, state: State<AppState>) -> Result<(), String> {
-    let conn = state.multimedia_db.lock().unwrap();
-    conn.execute("INSERT INTO imagenes (nombre, ruta, aspecto) VALUES (?, ?, 'contain')", params![nombre, ruta]).map_err(|e| e.to_string())?;
+async fn add_image_db(app: tauri::AppHandle, nombre: String, ruta: String, opciones: Option<imagen::ImagenImportOpciones>, state: State<'_, AppState>) -> Result<(), String> {
+    importar_imagen(&app, &nombre, &ruta, opciones, &state)?;
+    Ok(())
+}
+
+fn importar_imagen(app: &tauri::AppHandle, nombre: &str, ruta: &str, opciones: Option<imagen::ImagenImportOpciones>, state: &AppState) -> Result<i64, String> {
+    let opciones = opciones.unwrap_or_default();
+    let id = {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO imagenes (nombre, ruta, aspecto, corregir_orientacion, reducir, rotacion) VALUES (?, ?, 'contain', ?, ?, 0)",
+            params![nombre, ruta, opciones.corregir_orientacion, opciones.reducir]
+        ).map_err(|e| e.to_string())?;
+        conn.last_insert_rowid()
+    };
+    // Si el archivo no se puede procesar se proyecta el original, igual que antes
+    if let Err(e) = regenerar_imagen(app, id as i32, state) {
+        println!("No se pudo procesar la imagen {}: {}", ruta, e);
+    }
+    Ok(id)
+}
+
+// Vuelve a generar la versión proyectada a partir del original y los parámetros guardados
+fn regenerar_imagen(app: &tauri::AppHandle, id: i32, state: &AppState) -> Result<(), String> {
+    let actual = {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        conn.query_row(&format!("SELECT {} FROM imagenes WHERE id = ?", imagen_cols()), params![id], read_imagen).map_err(|e| e.to_string())?
+    };
+
+    let carpeta = app.path().app_data_dir().map_err(|e| e.to_string())?.join("imagenes");
+    fs::create_dir_all(&carpeta).map_err(|e| e.to_string())?;
+    let origen = PathBuf::from(&actual.ruta_original);
+    // Nombre nuevo en cada cambio para que el webview no muestre la versión anterior en caché
+    let stamp = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis();
+    let salida = carpeta.join(format!("{}_{}.{}", id, stamp, imagen::extension_salida(&origen)));
+
+    let generada = imagen::procesar(&origen, &actual.opciones, &actual.edicion, resolucion_proyector(app), &salida)?;
+    let nueva = generada.then(|| salida.to_string_lossy().to_string());
+
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("UPDATE imagenes SET ruta_proyeccion = ? WHERE id = ?", params![nueva, id]).map_err(|e| e.to_string())?;
+    if actual.ruta != actual.ruta_original {
+        let _ = fs::remove_file(&actual.ruta);
+    }
     Ok(())
 }
 
 #[tauri::command]
 fn delete_image_db(id: i32, state: State<AppState>) -> Result<(), String> {
     let conn = state.multimedia_db.lock().unwrap();
+    let procesada: Option<String> = conn.query_row("SELECT ruta_proyeccion FROM imagenes WHERE id = ?", params![id], |row| row.get(0)).optional().map_err(|e| e.to_string())?.flatten();
     conn.execute("DELETE FROM imagenes WHERE id = ?", params![id]).map_err(|e| e.to_string())?;
+    biblioteca::borrar_etiquetas(&conn, MediaTipo::Imagen, id).map_err(|e| e.to_string())?;
+    if let Some(procesada) = procesada {
+        let _ = fs::remove_file(procesada);
+    }
     Ok(())
 }
 
+// Guarda el recorte/rotación y regenera la versión proyectada. Devuelve la imagen actualizada.
+#[tauri::command]
+async fn update_image_edit(app: tauri::AppHandle, id: i32, edicion: imagen::ImagenEdicion, opciones: Option<imagen::ImagenImportOpciones>, state: State<'_, AppState>) -> Result<Imagen, String> {
+    edicion.validar()?;
+    {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        let original: String = conn.query_row("SELECT ruta FROM imagenes WHERE id = ?", params![id], |row| row.get(0)).map_err(|e| e.to_string())?;
+        if (edicion.rotacion != 0 || edicion.recorte.is_some()) && imagen::es_animada(&PathBuf::from(&original)) {
+            return Err("Las imágenes animadas no se pueden recortar ni rotar sin perder la animación".to_string());
+        }
+        let recorte = edicion.recorte;
+        conn.execute(
+            "UPDATE imagenes SET rotacion = ?, recorte_x = ?, recorte_y = ?, recorte_ancho = ?, recorte_alto = ? WHERE id = ?",
+            params![edicion.rotacion, recorte.map(|r| r.x), recorte.map(|r| r.y), recorte.map(|r| r.ancho), recorte.map(|r| r.alto), id]
+        ).map_err(|e| e.to_string())?;
+        if let Some(opciones) = opciones {
+            conn.execute("UPDATE imagenes SET corregir_orientacion = ?, reducir = ? WHERE id = ?", params![opciones.corregir_orientacion, opciones.reducir, id]).map_err(|e| e.to_string())?;
+        }
+    }
+    regenerar_imagen(&app, id, &state)?;
+
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.query_row(&format!("SELECT {} FROM imagenes WHERE id = ?", imagen_cols()), params![id], read_imagen).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 fn update_image_aspect(id: i32, aspecto: String, state: State<AppState>) -> Result<(), String> {
     let conn = state.multimedia_db.lock().unwrap();
@@ -144,38 +296,142 @@ fn update_image_aspect(id: i32, aspecto: String, state: State<AppState>) -> Resu
 // COMANDOS DE VIDEOS
 // ==========================================
 #[tauri::command]
-fn get_all_videos(state: State<AppState>) -> Result<Vec<Video>, String> {
+fn get_all_videos(filtro: Option<MediaFiltro>, state: State<AppState>) -> Result<Vec<Video>, String> {
+    let (clausulas, valores) = biblioteca::clausulas(MediaTipo::Video, &filtro.unwrap_or_default(), "reciente")?;
     let conn = state.multimedia_db.lock().unwrap();
-    let mut stmt = conn.prepare("SELECT id, nombre, ruta, COALESCE(bucle, 0) FROM videos ORDER BY id DESC").unwrap();
-    let iter = stmt.query_map([], |row| Ok(Video { 
-        id: row.get(0)?, 
-        nombre: row.get(1)?, 
-        ruta: row.get(2)?,
-        bucle: row.get::<_, i32>(3)? != 0 // Convertir INTEGER (0/1) a bool
-    })).unwrap();
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM videos{}", video_cols(), clausulas)).map_err(|e| e.to_string())?;
+    let iter = stmt.query_map(rusqlite::params_from_iter(valores), read_video).map_err(|e| e.to_string())?;
     Ok(iter.filter_map(Result::ok).collect())
 }
 
+fn video_cols() -> String {
+    format!("id, nombre, ruta, COALESCE(bucle, 0), duracion, ancho, alto, codec, tiene_audio, poster, {}", biblioteca::columnas_biblioteca(MediaTipo::Video))
+}
+
+fn read_video(row: &rusqlite::Row) -> rusqlite::Result<Video> {
+    let ruta: String = row.get(2)?;
+    let codec: Option<String> = row.get(7)?;
+    Ok(Video {
+        id: row.get(0)?,
+        nombre: row.get(1)?,
+        advertencias: ffmpeg::advertencias_compatibilidad(&ruta, codec.as_deref()),
+        ruta,
+        bucle: row.get::<_, i32>(3)? != 0, // Convertir INTEGER (0/1) a bool
+        duracion: row.get(4)?,
+        ancho: row.get(5)?,
+        alto: row.get(6)?,
+        codec,
+        tiene_audio: row.get::<_, Option<i32>>(8)?.map(|a| a != 0),
+        poster: row.get(9)?,
+        biblioteca: biblioteca::leer_info(row, 10)?,
+    })
+}
+
+// Analiza el video con ffprobe y genera la miniatura con ffmpeg (si están instalados).
+// El análisis se devuelve aparte: cada llamador decide si un fallo de ffprobe es un error.
+fn analizar_video(app: &tauri::AppHandle, ruta: &str, state: &AppState) -> Result<(Result<ffmpeg::VideoProbe, String>, Option<String>), String> {
+    let (ffprobe, ffmpeg_bin) = {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        (ffmpeg::buscar_binario("ffprobe", leer_config(&conn, ffmpeg::CONFIG_FFPROBE)), ffmpeg::buscar_binario("ffmpeg", leer_config(&conn, ffmpeg::CONFIG_FFMPEG)))
+    };
+
+    let probe = match ffprobe {
+        Some(ffprobe) => ffmpeg::probar_video(&ffprobe, ruta),
+        None => Ok(ffmpeg::VideoProbe::default()),
+    };
+
+    let poster = ffmpeg_bin.and_then(|ffmpeg_bin| {
+        let carpeta = app.path().app_data_dir().ok()?.join("posters");
+        fs::create_dir_all(&carpeta).ok()?;
+        let salida = carpeta.join(format!("{}.jpg", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis()));
+        // Un fotograma cerca del inicio, evitando el negro de la primera imagen
+        let segundo = probe.as_ref().ok().and_then(|p| p.duracion).map(|d| (d * 0.1).min(3.0)).unwrap_or(1.0);
+        ffmpeg::generar_poster(&ffmpeg_bin, ruta, segundo, &salida).ok()?;
+        Some(salida.to_string_lossy().to_string())
+    });
+
+    Ok((probe, poster))
+}
+
 #[tauri::command]
-fn add_video_db(nombre: String, ruta: String, state: State<AppState>) -> Result<(), String> {
-    let conn = state.multimedia_db.lock().unwrap();
-    conn.execute("INSERT INTO videos (nombre, ruta, bucle) VALUES (?, ?, 0)", params![nombre, ruta]).map_err(|e| e.to_string())?;
-    Ok(())
+async fn add_video_db(app: tauri::AppHandle, nombre: String, ruta: String, state: State<'_, AppState>) -> Result<Video, String> {
+    importar_video(&app, &nombre, &ruta, &state)
+}
+
+fn importar_video(app: &tauri::AppHandle, nombre: &str, ruta: &str, state: &AppState) -> Result<Video, String> {
+    // Si ffprobe no entiende el archivo se importa igual, sin duración ni resolución
+    let (probe, poster) = analizar_video(app, ruta, state)?;
+    let probe = probe.unwrap_or_else(|e| {
+        println!("ffprobe no pudo analizar {}: {}", ruta, e);
+        ffmpeg::VideoProbe::default()
+    });
+
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO videos (nombre, ruta, bucle, duracion, ancho, alto, codec, tiene_audio, poster) VALUES (?, ?, 0, ?, ?, ?, ?, ?, ?)",
+        params![nombre, ruta, probe.duracion, probe.ancho, probe.alto, probe.codec, probe.tiene_audio, poster]
+    ).map_err(|e| e.to_string())?;
+    conn.query_row(&format!("SELECT {} FROM videos WHERE id = ?", video_cols()), params![conn.last_insert_rowid()], read_video).map_err(|e| e.to_string())
+}
+
+// Vuelve a analizar un video ya importado (ej: después de instalar ffmpeg)
+#[tauri::command]
+async fn refresh_video_metadata(app: tauri::AppHandle, id: i32, state: State<'_, AppState>) -> Result<Video, String> {
+    let (ruta, poster_anterior): (String, Option<String>) = {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        conn.query_row("SELECT ruta, poster FROM videos WHERE id = ?", params![id], |row| Ok((row.get(0)?, row.get(1)?))).map_err(|e| e.to_string())?
+    };
+    let (probe, poster) = analizar_video(&app, &ruta, &state)?;
+    let probe = match probe {
+        Ok(probe) => probe,
+        Err(e) => {
+            if let Some(nuevo) = &poster { let _ = fs::remove_file(nuevo); }
+            return Err(e);
+        }
+    };
+    if let (Some(anterior), Some(_)) = (&poster_anterior, &poster) {
+        let _ = fs::remove_file(anterior);
+    }
+
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE videos SET duracion = ?, ancho = ?, alto = ?, codec = ?, tiene_audio = ?, poster = COALESCE(?, poster) WHERE id = ?",
+        params![probe.duracion, probe.ancho, probe.alto, probe.codec, probe.tiene_audio, poster, id]
+    ).map_err(|e| e.to_string())?;
+    conn.query_row(&format!("SELECT {} FROM videos WHERE id = ?", video_cols()), params![id], read_video).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 fn delete_video_db(id: i32, state: State<AppState>) -> Result<(), String> {
     let conn = state.multimedia_db.lock().unwrap();
+    let poster: Option<String> = conn.query_row("SELECT poster FROM videos WHERE id = ?", params![id], |row| row.get(0)).optional().map_err(|e| e.to_string())?.flatten();
     conn.execute("DELETE FROM videos WHERE id = ?", params![id]).map_err(|e| e.to_string())?;
+    biblioteca::borrar_etiquetas(&conn, MediaTipo::Video, id).map_err(|e| e.to_string())?;
+    if let Some(poster) = poster {
+        let _ = fs::remove_file(poster);
+    }
     Ok(())
 }
 
 // NUEVO COMANDO: Bucle de video
 #[tauri::command]
-fn update_video_loop(id: i32, bucle: bool, state: State<AppState>) -> Result<(), String> {
+fn update_video_loop(app: tauri::AppHandle, id: i32, bucle: bool, state: State<AppState>) -> Result<(), String> {
     let conn = state.multimedia_db.lock().unwrap();
     let b_val = if bucle { 1 } else { 0 };
     conn.execute("UPDATE videos SET bucle = ? WHERE id = ?", params![b_val, id]).map_err(|e| e.to_string())?;
+
+    // Si ese video está en el proyector, el cambio de bucle se aplica en vivo
+    let ruta: String = conn.query_row("SELECT ruta FROM videos WHERE id = ?", params![id], |row| row.get(0)).map_err(|e| e.to_string())?;
+    let en_vivo = state.video_estado.lock().map_err(|e| e.to_string())?.as_ref().is_some_and(|v| v.ruta == ruta);
+    if en_vivo {
+        let evento = EventoProyeccion::Video(VideoControl::Loop { activo: bucle });
+        if let Some(projector_window) = app.get_webview_window("projector") {
+            protocolo::emitir(&projector_window, &evento);
+        }
+        for ventana in salidas::ventanas_con_capa(&app, salidas::Capa::Multimedia) {
+            protocolo::emitir(&ventana, &evento);
+        }
+    }
     Ok(())
 }
 
@@ -189,11 +445,78 @@ async fn select_video_file(app: tauri::AppHandle) -> Option<String> {
     file_path.map(|path| path.to_string())
 }
 
+// Protocolo de control de video hacia el proyector (ej: { "accion": "seek", "segundos": 30 })
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "accion", rename_all = "snake_case")]
+pub(crate) enum VideoControl {
+    Play,
+    Pause,
+    Restart,
+    Seek { segundos: f64 },
+    Volume { nivel: f64 },   // 0.0 - 1.0
+    Mute { activo: bool },
+    Loop { activo: bool },
+}
+
+impl VideoControl {
+    fn validar(&self) -> Result<(), String> {
+        match self {
+            VideoControl::Seek { segundos } if !segundos.is_finite() || *segundos < 0.0 => Err("La posición del video no es válida".to_string()),
+            VideoControl::Volume { nivel } if !(0.0..=1.0).contains(nivel) => Err("El volumen debe estar entre 0 y 1".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Estado que reporta el proyector mientras reproduce un video
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+struct VideoEstado {
+    ruta: String,
+    posicion: f64,      // Segundos
+    duracion: f64,
+    reproduciendo: bool,
+    volumen: f64,
+    silencio: bool,
+    bucle: bool,
+    terminado: bool,
+}
+
 #[tauri::command]
-fn trigger_video_control(app: tauri::AppHandle, action: String) {
+fn trigger_video_control(app: tauri::AppHandle, action: VideoControl) -> Result<(), String> {
+    action.validar()?;
+    let evento = EventoProyeccion::Video(action);
     if let Some(projector_window) = app.get_webview_window("projector") {
-        let _ = projector_window.emit("video-control", &action);
+        protocolo::emitir(&projector_window, &evento);
+    }
+    for ventana in salidas::ventanas_con_capa(&app, salidas::Capa::Multimedia) {
+        protocolo::emitir(&ventana, &evento);
+    }
+    Ok(())
+}
+
+// El proyector informa posición, duración y fin del video; se reenvía al panel de control
+#[tauri::command]
+fn report_video_state(app: tauri::AppHandle, window: tauri::WebviewWindow, estado: VideoEstado, state: State<AppState>) -> Result<(), String> {
+    // Las salidas adicionales reproducen el mismo video; solo cuenta el proyector principal
+    if window.label() != "projector" { return Ok(()); }
+    let recien_terminado = {
+        let mut actual = state.video_estado.lock().map_err(|e| e.to_string())?;
+        let ya_terminado = actual.as_ref().is_some_and(|a| a.terminado && a.ruta == estado.ruta);
+        *actual = Some(estado.clone());
+        estado.terminado && !ya_terminado
+    };
+
+    let panel = EventTarget::webview_window("main");
+    let _ = app.emit_to(panel.clone(), "video-state", &estado);
+    if recien_terminado {
+        let _ = app.emit_to(panel, "video-ended", &estado.ruta);
     }
+    Ok(())
+}
+
+#[tauri::command]
+fn get_video_state(state: State<AppState>) -> Result<Option<VideoEstado>, String> {
+    Ok(state.video_estado.lock().map_err(|e| e.to_string())?.clone())
 }
 
 // ==========================================
@@ -206,13 +529,6 @@ async fn select_background_image(app: tauri::AppHandle) -> Option<String> {
     file_path.map(|path| path.to_string())
 }
 
-#[tauri::command]
-fn trigger_style_update(app: tauri::AppHandle, styles: serde_json::Value) {
-    if let Some(projector_window) = app.get_webview_window("projector") {
-        let _ = projector_window.emit("update-styles", &styles);
-    }
-}
-
 #[tauri::command]
 fn get_bible_versions(state: State<AppState>) -> Result<Vec<String>, String> {
     let conn = state.biblias_db.lock().unwrap();
@@ -247,71 +563,253 @@ fn get_single_verse(version: String, book: String, cap: i32, ver: i32, state: St
     ).optional().unwrap_or(None)
 }
 
+// `estilo` son los cambios propios del elemento; el proyector recibe el estilo ya resuelto
 #[tauri::command]
-fn trigger_projection(app: tauri::AppHandle, verse: serde_json::Value) {
-    if let Some(projector_window) = app.get_webview_window("projector") {
-        let _ = projector_window.emit("update-proyeccion", &verse);
-    }
+fn trigger_projection(app: tauri::AppHandle, verse: serde_json::Value, estilo: Option<temas::EstiloParcial>, state: State<AppState>) -> Result<(), String> {
+    if let Some(cambios) = &estilo { cambios.validar()?; }
+    // Se valida contra el protocolo y se guarda en su forma canónica (con `tipo` y `protocolo`)
+    let verse = protocolo::Contenido::desde_payload(verse)?.a_payload()?;
+    // Lo que manda el operador reemplaza al carrusel en curso
+    carrusel::detener(&app, &state)?;
+    proyectar(&app, &state, verse, estilo)
 }
 
-#[tauri::command]
-async fn open_projector(app: tauri::AppHandle) {
-    if let Some(window) = app.get_webview_window("projector") {
-        let _ = window.set_focus();
-        return;
+// Camino común de todo lo que sale al aire (panel y carrusel): se guarda como
+// proyección actual, se aplican las reglas de OBS y se avisa el estado en vivo
+fn proyectar(app: &tauri::AppHandle, state: &AppState, verse: serde_json::Value, estilo: Option<temas::EstiloParcial>) -> Result<(), String> {
+    // Con la pantalla congelada lo nuevo queda preparado y sale al descongelar
+    let congelada = pantalla::congelada(state);
+    if !congelada {
+        emitir_proyeccion(app, state, &verse, estilo.as_ref())?;
+    }
+    let anterior = state.proyeccion.lock().map_err(|e| e.to_string())?.replace((verse.clone(), estilo)).map(|(payload, _)| payload);
+    // Reglas de OBS que empiezan a cumplirse con lo que sale al aire
+    if !congelada {
+        obs::al_proyectar(app, anterior.as_ref(), &verse);
     }
-    let win_builder = WebviewWindowBuilder::new(&app, "projector", WebviewUrl::App("/projector".into()))
-            .title("Proyector")
-            .inner_size(800.0, 600.0);
+    pantalla::emitir_en_vivo(app);
+    Ok(())
+}
 
-    if let Ok(window) = win_builder.build() {
-        if let Ok(monitors) = window.available_monitors() {
-            if monitors.len() > 1 {
-                let _ = window.set_position(monitors[1].position().clone());
-                let _ = window.set_fullscreen(true);
-            }
-        }
+// El proyector recibe el estilo ya resuelto; las salidas adicionales, su propia versión según capas y tema
+fn emitir_proyeccion(app: &tauri::AppHandle, state: &AppState, original: &serde_json::Value, estilo: Option<&temas::EstiloParcial>) -> Result<(), String> {
+    let contenido = protocolo::Contenido::desde_payload(original.clone())?;
+    let resuelto = temas::resolver_estilo(state, original, estilo)?;
+    let transicion = transiciones::resolver(state, resuelto.as_ref(), estilo);
+    if let Some(projector_window) = app.get_webview_window("projector") {
+        let proyeccion = protocolo::Proyeccion { contenido, estilo: resuelto, transicion };
+        protocolo::emitir(&projector_window, &EventoProyeccion::Contenido(Some(proyeccion)));
     }
+    salidas::emitir_a_salidas(app, original, estilo)
+}
+
+#[tauri::command]
+async fn open_projector(app: tauri::AppHandle) -> Result<(), String> {
+    // El monitor sale de la asignación guardada (ver salidas.rs)
+    salidas::abrir_salida(&app, salidas::SALIDA_PROYECTOR, "projector", "/projector", "Proyector")
 }
 
 // ==========================================
 // COMANDOS PDF
 // ==========================================
 #[derive(Serialize)]
-struct PdfDoc { id: i32, nombre: String, ruta: String }
+struct PdfDoc { id: i32, nombre: String, ruta: String, paginas: i32, opciones: PdfRenderOpciones, #[serde(flatten)] biblioteca: InfoBiblioteca }
+
+// Una página ya convertida a imagen, con sus medidas y el texto que extrae pdfium
+#[derive(Serialize, Clone, Debug)]
+struct PdfPagina {
+    pdf_id: i32,
+    numero: i32,
+    archivo: String,   // Ruta completa de la imagen
+    ancho: u32,        // Píxeles de la imagen generada
+    alto: u32,
+    ancho_pt: f32,     // Tamaño original de la página en puntos PDF
+    alto_pt: f32,
+    texto: String,
+}
+
+// Opciones de renderizado. Se guardan en la tabla `pdfs` para poder re-renderizar el documento después.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+struct PdfRenderOpciones {
+    ancho: Option<u32>,          // None = resolución del monitor del proyector
+    alto: Option<u32>,
+    pagina_inicio: Option<u16>,  // Rango de páginas (1-based, inclusivo)
+    pagina_fin: Option<u16>,
+    formato: String,             // "jpg" | "png" | "webp" (png y webp son sin pérdida)
+    calidad: u8,                 // Solo aplica a JPEG (1-100)
+    fondo: String,               // Color para páginas transparentes, "#RRGGBB"
+}
+
+impl Default for PdfRenderOpciones {
+    fn default() -> Self {
+        PdfRenderOpciones { ancho: None, alto: None, pagina_inicio: None, pagina_fin: None, formato: "jpg".to_string(), calidad: 90, fondo: "#FFFFFF".to_string() }
+    }
+}
+
+impl PdfRenderOpciones {
+    // Valida los valores y completa la resolución con la del monitor del proyector (1920x1080 si no hay)
+    fn resolver(mut self, app: &tauri::AppHandle) -> Result<Self, String> {
+        self.formato = self.formato.to_lowercase().replace("jpeg", "jpg");
+        if !["jpg", "png", "webp"].contains(&self.formato.as_str()) {
+            return Err(format!("Formato de imagen no soportado: {}", self.formato));
+        }
+        if self.calidad == 0 || self.calidad > 100 {
+            return Err("La calidad JPEG debe estar entre 1 y 100".to_string());
+        }
+        parse_color_hex(&self.fondo)?;
+        if let (Some(inicio), Some(fin)) = (self.pagina_inicio, self.pagina_fin) {
+            if inicio > fin { return Err("El rango de páginas no es válido".to_string()); }
+        }
+        if self.ancho.is_none() && self.alto.is_none() {
+            let (ancho, alto) = resolucion_proyector(app);
+            self.ancho = Some(ancho);
+            self.alto = Some(alto);
+        }
+        Ok(self)
+    }
+}
+
+fn parse_color_hex(color: &str) -> Result<(u8, u8, u8), String> {
+    let hex = color.trim_start_matches('#');
+    let error = || format!("Color inválido: {}", color);
+    if hex.len() != 6 { return Err(error()); }
+    let canal = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| error());
+    Ok((canal(0)?, canal(2)?, canal(4)?))
+}
+
+// Resolución del monitor donde se abre (o se abriría) el proyector
+fn resolucion_proyector(app: &tauri::AppHandle) -> (u32, u32) {
+    let monitor = match app.get_webview_window("projector") {
+        Some(window) => window.current_monitor().ok().flatten(),
+        None => match salidas::monitor_de_salida(app, salidas::SALIDA_PROYECTOR) {
+            Some(monitor) => return (monitor.ancho, monitor.alto),
+            None => app.primary_monitor().ok().flatten(),
+        },
+    };
+    monitor.map(|m| (m.size().width, m.size().height)).unwrap_or((1920, 1080))
+}
+
+fn read_pdf_opciones(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<PdfRenderOpciones> {
+    Ok(PdfRenderOpciones {
+        ancho: row.get(offset)?,
+        alto: row.get(offset + 1)?,
+        pagina_inicio: row.get(offset + 2)?,
+        pagina_fin: row.get(offset + 3)?,
+        formato: row.get(offset + 4)?,
+        calidad: row.get(offset + 5)?,
+        fondo: row.get(offset + 6)?,
+    })
+}
+
+const PDF_OPCIONES_COLS: &str = "ancho, alto, pagina_inicio, pagina_fin, COALESCE(formato, 'jpg'), COALESCE(calidad, 90), COALESCE(fondo, '#FFFFFF')";
 
 #[tauri::command]
-async fn get_all_pdfs(state: State<'_, AppState>) -> Result<Vec<PdfDoc>, String> {
+async fn get_all_pdfs(filtro: Option<MediaFiltro>, state: State<'_, AppState>) -> Result<Vec<PdfDoc>, String> {
+    let (clausulas, valores) = biblioteca::clausulas(MediaTipo::Pdf, &filtro.unwrap_or_default(), "reciente")?;
     let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
-    let mut stmt = conn.prepare("SELECT id, nombre, ruta FROM pdfs ORDER BY id DESC").unwrap();
-    let iter = stmt.query_map([], |row| Ok(PdfDoc { id: row.get(0)?, nombre: row.get(1)?, ruta: row.get(2)? })).unwrap();
+    let sql = format!("SELECT id, nombre, ruta, COALESCE(paginas, 0), {}, {} FROM pdfs{}", PDF_OPCIONES_COLS, biblioteca::columnas_biblioteca(MediaTipo::Pdf), clausulas);
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let iter = stmt.query_map(rusqlite::params_from_iter(valores), |row| Ok(PdfDoc {
+        id: row.get(0)?, nombre: row.get(1)?, ruta: row.get(2)?, paginas: row.get(3)?, opciones: read_pdf_opciones(row, 4)?,
+        biblioteca: biblioteca::leer_info(row, 11)?
+    })).map_err(|e| e.to_string())?;
     Ok(iter.filter_map(Result::ok).collect())
 }
 
 #[tauri::command]
-async fn add_pdf_db(app: tauri::AppHandle, nombre: String, ruta: String, state: State<'_, AppState>) -> Result<(), String> {
+async fn add_pdf_db(app: tauri::AppHandle, nombre: String, ruta: String, opciones: Option<PdfRenderOpciones>, state: State<'_, AppState>) -> Result<(), String> {
+    importar_pdf(&app, &nombre, &ruta, opciones, &state)?;
+    Ok(())
+}
+
+// Convierte un PDF a imágenes y lo registra en la biblioteca. Lo comparten los PDFs y las presentaciones convertidas.
+fn importar_pdf(app: &tauri::AppHandle, nombre: &str, ruta: &str, opciones: Option<PdfRenderOpciones>, state: &AppState) -> Result<i64, String> {
+    let opciones = opciones.unwrap_or_default().resolver(app)?;
+
     // 1. Obtenemos la carpeta de datos de tu aplicación
     let app_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
-    
+
     // 2. Creamos una subcarpeta única para este PDF (ej: /pdfs_MiPresentacion_17080000)
-    let safe_name = nombre.replace(" ", "_").replace(".pdf", "");
+    let safe_name = std::path::Path::new(nombre).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default().replace(" ", "_");
     let folder_name = format!("pdfs_{}_{}", safe_name, std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs());
     let output_folder = app_dir.join(&folder_name);
 
     // 3. Ejecutamos el motor para convertir el PDF a imágenes dentro de esa carpeta
-    extraer_paginas_pdf(&app, &ruta, &output_folder).map_err(|e| format!("Error procesando PDF: {}", e))?;
+    let paginas = extraer_paginas_pdf(app, ruta, &output_folder, &opciones).map_err(|e| {
+        let _ = fs::remove_dir_all(&output_folder);
+        format!("Error procesando PDF: {}", e)
+    })?;
 
-    // 4. Guardamos la ruta de la CARPETA en la base de datos (NO el archivo .pdf original)
+    // 4. Guardamos la ruta de la CARPETA en la base de datos junto con el original, las opciones usadas y las páginas
     let folder_path_str = output_folder.to_string_lossy().to_string();
-    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
-    conn.execute("INSERT INTO pdfs (nombre, ruta) VALUES (?, ?)", params![nombre, folder_path_str]).map_err(|e| e.to_string())?;
+    let mut conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO pdfs (nombre, ruta, ruta_original, ancho, alto, pagina_inicio, pagina_fin, formato, calidad, fondo, paginas) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        params![nombre, folder_path_str, ruta, opciones.ancho, opciones.alto, opciones.pagina_inicio, opciones.pagina_fin, opciones.formato, opciones.calidad, opciones.fondo, paginas.len() as i32]
+    ).map_err(|e| e.to_string())?;
+    let pdf_id = tx.last_insert_rowid();
+    guardar_paginas_pdf(&tx, pdf_id as i32, &paginas).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(pdf_id)
+}
+
+// Vuelve a generar las páginas de un PDF ya importado con otras opciones (ej: al cambiar a un proyector 4K)
+#[tauri::command]
+async fn rerender_pdf(app: tauri::AppHandle, id: i32, opciones: PdfRenderOpciones, state: State<'_, AppState>) -> Result<(), String> {
+    let opciones = opciones.resolver(&app)?;
+
+    let (carpeta, ruta_original): (String, Option<String>) = {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        conn.query_row("SELECT ruta, ruta_original FROM pdfs WHERE id = ?", params![id], |row| Ok((row.get(0)?, row.get(1)?))).map_err(|e| e.to_string())?
+    };
+    let ruta_original = ruta_original.ok_or("Este PDF se importó sin guardar el archivo original. Vuelva a importarlo.")?;
+
+    // Se renderiza en una carpeta aparte y solo si todo sale bien reemplaza a la actual,
+    // así un error no deja el PDF sin páginas
+    let output_folder = PathBuf::from(&carpeta);
+    let temporal = PathBuf::from(format!("{}.nuevo", carpeta));
+    let anterior = PathBuf::from(format!("{}.anterior", carpeta));
+    for sobrante in [&temporal, &anterior] {
+        if sobrante.exists() { fs::remove_dir_all(sobrante).map_err(|e| e.to_string())?; }
+    }
+    let mut paginas = match extraer_paginas_pdf(&app, &ruta_original, &temporal, &opciones) {
+        Ok(paginas) => paginas,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&temporal);
+            return Err(format!("Error procesando PDF: {}", e));
+        }
+    };
+    if output_folder.exists() {
+        fs::rename(&output_folder, &anterior).map_err(|e| e.to_string())?;
+    }
+    if let Err(e) = fs::rename(&temporal, &output_folder) {
+        let _ = fs::rename(&anterior, &output_folder);
+        return Err(e.to_string());
+    }
+    let _ = fs::remove_dir_all(&anterior);
+    for pagina in &mut paginas {
+        pagina.archivo = output_folder.join(format!("{}.{}", pagina.numero, opciones.formato)).to_string_lossy().to_string();
+    }
+
+    let mut conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "UPDATE pdfs SET ancho = ?, alto = ?, pagina_inicio = ?, pagina_fin = ?, formato = ?, calidad = ?, fondo = ?, paginas = ? WHERE id = ?",
+        params![opciones.ancho, opciones.alto, opciones.pagina_inicio, opciones.pagina_fin, opciones.formato, opciones.calidad, opciones.fondo, paginas.len() as i32, id]
+    ).map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM pdf_paginas WHERE pdf_id = ?", params![id]).map_err(|e| e.to_string())?;
+    guardar_paginas_pdf(&tx, id, &paginas).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
 
     Ok(())
 }
 
 // El motor de extracción (Versión Multi-núcleo ultra rápida)
 // El motor de extracción Multi-hilo con Progreso
-fn extraer_paginas_pdf(app: &tauri::AppHandle, ruta_pdf: &str, ruta_carpeta_salida: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+fn extraer_paginas_pdf(app: &tauri::AppHandle, ruta_pdf: &str, ruta_carpeta_salida: &std::path::Path, opciones: &PdfRenderOpciones) -> Result<Vec<PdfPagina>, Box<dyn std::error::Error>> {
     let _ = app.emit("pdf-progress", serde_json::json!({ "current": 0, "total": 0, "status": "Iniciando motor PDF..." }));
 
     // --- AQUÍ VA EL CÓDIGO DE SELECCIÓN DE LIBRERÍA ---
@@ -332,39 +830,139 @@ fn extraer_paginas_pdf(app: &tauri::AppHandle, ruta_pdf: &str, ruta_carpeta_sali
     let documento = pdfium.load_pdf_from_file(ruta_pdf, None)?;
     fs::create_dir_all(ruta_carpeta_salida)?;
 
-    let total_paginas = documento.pages().len() as usize;
+    // Rango de páginas a renderizar (1-based, limitado al tamaño real del documento)
+    let paginas_documento = documento.pages().len();
+    let inicio = opciones.pagina_inicio.unwrap_or(1).max(1);
+    let fin = opciones.pagina_fin.unwrap_or(paginas_documento).min(paginas_documento);
+    if inicio > fin {
+        return Err(format!("El documento solo tiene {} páginas", paginas_documento).into());
+    }
+
+    let total_paginas = (fin - inicio + 1) as usize;
     let _ = app.emit("pdf-progress", serde_json::json!({ "current": 0, "total": total_paginas, "status": "Leyendo documento..." }));
 
+    // La página se ajusta dentro de la resolución objetivo sin deformarse
+    let (r, g, b) = parse_color_hex(&opciones.fondo)?;
+    let mut config = PdfRenderConfig::new().set_clear_color(PdfColor::new(r, g, b, 255));
+    if let Some(ancho) = opciones.ancho {
+        config = config.set_target_width(ancho as i32).set_maximum_width(ancho as i32);
+    }
+    if let Some(alto) = opciones.alto {
+        config = config.set_maximum_height(alto as i32);
+        if opciones.ancho.is_none() { config = config.set_target_height(alto as i32); }
+    }
+
+    // Las páginas se numeran desde 1 aunque el rango empiece más adelante: el visor recorre 1..=paginas
     let mut imagenes_temporales = Vec::new();
-    for (indice, pagina) in documento.pages().iter().enumerate() {
-        let bitmap = pagina.render_with_config(&PdfRenderConfig::new().set_target_width(1920))?;
-        imagenes_temporales.push((indice + 1, bitmap.as_image())); 
+    let mut paginas = Vec::new();
+    for (indice, num_pagina) in (inicio..=fin).enumerate() {
+        let numero = indice + 1;
+        let pagina = documento.pages().get(num_pagina - 1)?;
+        let bitmap = pagina.render_with_config(&config)?;
+        let imagen = bitmap.as_image();
+        paginas.push(PdfPagina {
+            pdf_id: 0,
+            numero: numero as i32,
+            archivo: ruta_carpeta_salida.join(format!("{}.{}", numero, opciones.formato)).to_string_lossy().to_string(),
+            ancho: imagen.width(),
+            alto: imagen.height(),
+            ancho_pt: pagina.width().value,
+            alto_pt: pagina.height().value,
+            texto: pagina.text().map(|t| t.all().trim().to_string()).unwrap_or_default(),
+        });
+        imagenes_temporales.push((numero, imagen));
     }
 
     // Configuramos los rastreadores multi-hilo
     let procesadas = Arc::new(AtomicUsize::new(0));
     let app_h = app.clone(); // Clonamos el manejador para usarlo en los hilos
+    let formato = opciones.formato.clone();
+    let calidad = opciones.calidad;
 
     // Comprimimos usando todos los núcleos y reportamos progreso
-    imagenes_temporales.into_par_iter().for_each(move |(num_pagina, imagen)| {
-        let ruta_imagen = ruta_carpeta_salida.join(format!("{}.jpg", num_pagina));
-        let _ = imagen.into_rgb8().save_with_format(ruta_imagen, image::ImageFormat::Jpeg);
-        
+    let errores: Vec<String> = imagenes_temporales.into_par_iter().filter_map(move |(numero, imagen)| {
+        let ruta_imagen = ruta_carpeta_salida.join(format!("{}.{}", numero, formato));
+        let resultado = guardar_pagina(imagen, &ruta_imagen, &formato, calidad).err().map(|e| format!("página {}: {}", numero, e));
+
         let actual = procesadas.fetch_add(1, Ordering::Relaxed) + 1;
-        let _ = app_h.emit("pdf-progress", serde_json::json!({ 
-            "current": actual, 
-            "total": total_paginas, 
-            "status": "Optimizando imágenes (Multi-núcleo)..." 
+        let _ = app_h.emit("pdf-progress", serde_json::json!({
+            "current": actual,
+            "total": total_paginas,
+            "status": "Optimizando imágenes (Multi-núcleo)..."
         }));
-    });
+        resultado
+    }).collect();
+    if !errores.is_empty() {
+        return Err(format!("No se pudieron guardar {} páginas ({})", errores.len(), errores.join("; ")).into());
+    }
 
+    Ok(paginas)
+}
+
+fn guardar_paginas_pdf(conn: &Connection, pdf_id: i32, paginas: &[PdfPagina]) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare("INSERT INTO pdf_paginas (pdf_id, numero, archivo, ancho, alto, ancho_pt, alto_pt, texto) VALUES (?, ?, ?, ?, ?, ?, ?, ?)")?;
+    for p in paginas {
+        stmt.execute(params![pdf_id, p.numero, p.archivo, p.ancho, p.alto, p.ancho_pt, p.alto_pt, p.texto])?;
+    }
+    Ok(())
+}
+
+fn read_pdf_pagina(row: &rusqlite::Row) -> rusqlite::Result<PdfPagina> {
+    Ok(PdfPagina { pdf_id: row.get(0)?, numero: row.get(1)?, archivo: row.get(2)?, ancho: row.get(3)?, alto: row.get(4)?, ancho_pt: row.get(5)?, alto_pt: row.get(6)?, texto: row.get(7)? })
+}
+
+// Páginas de un PDF en orden. Los PDFs importados antes de guardar las páginas se leen desde la carpeta.
+#[tauri::command]
+async fn get_pdf_pages(id: i32, state: State<'_, AppState>) -> Result<Vec<PdfPagina>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare("SELECT pdf_id, numero, archivo, ancho, alto, ancho_pt, alto_pt, COALESCE(texto, '') FROM pdf_paginas WHERE pdf_id = ? ORDER BY numero").map_err(|e| e.to_string())?;
+    let paginas: Vec<PdfPagina> = stmt.query_map(params![id], read_pdf_pagina).map_err(|e| e.to_string())?.filter_map(Result::ok).collect();
+    if !paginas.is_empty() {
+        return Ok(paginas);
+    }
+
+    let carpeta: String = conn.query_row("SELECT ruta FROM pdfs WHERE id = ?", params![id], |row| row.get(0)).map_err(|e| e.to_string())?;
+    let mut paginas: Vec<PdfPagina> = fs::read_dir(&carpeta).map_err(|e| e.to_string())?
+        .filter_map(Result::ok)
+        .filter_map(|entrada| {
+            let ruta = entrada.path();
+            let numero: i32 = ruta.file_stem()?.to_str()?.parse().ok()?;
+            let (ancho, alto) = image::image_dimensions(&ruta).unwrap_or((0, 0));
+            Some(PdfPagina { pdf_id: id, numero, archivo: ruta.to_string_lossy().to_string(), ancho, alto, ancho_pt: 0.0, alto_pt: 0.0, texto: String::new() })
+        })
+        .collect();
+    paginas.sort_by_key(|p| p.numero);
+    Ok(paginas)
+}
+
+// Búsqueda de texto dentro de las páginas de todos los PDFs
+#[tauri::command]
+async fn search_pdf_pages(texto: String, state: State<'_, AppState>) -> Result<Vec<PdfPagina>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare("SELECT pdf_id, numero, archivo, ancho, alto, ancho_pt, alto_pt, COALESCE(texto, '') FROM pdf_paginas WHERE texto LIKE ? ORDER BY pdf_id DESC, numero LIMIT 200").map_err(|e| e.to_string())?;
+    let iter = stmt.query_map(params![format!("%{}%", texto.trim())], read_pdf_pagina).map_err(|e| e.to_string())?;
+    Ok(iter.filter_map(Result::ok).collect())
+}
+
+fn guardar_pagina(imagen: image::DynamicImage, ruta: &std::path::Path, formato: &str, calidad: u8) -> Result<(), Box<dyn std::error::Error>> {
+    match formato {
+        "png" => imagen.save_with_format(ruta, image::ImageFormat::Png)?,
+        "webp" => imagen.into_rgba8().save_with_format(ruta, image::ImageFormat::WebP)?,
+        _ => {
+            let archivo = std::io::BufWriter::new(fs::File::create(ruta)?);
+            let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(archivo, calidad);
+            imagen.into_rgb8().write_with_encoder(encoder)?;
+        }
+    }
     Ok(())
 }
 
 #[tauri::command]
 async fn delete_pdf_db(id: i32, state: State<'_, AppState>) -> Result<(), String> {
     let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM pdf_paginas WHERE pdf_id = ?", params![id]).map_err(|e| e.to_string())?;
     conn.execute("DELETE FROM pdfs WHERE id = ?", params![id]).map_err(|e| e.to_string())?;
+    biblioteca::borrar_etiquetas(&conn, MediaTipo::Pdf, id).map_err(|e| e.to_string())?;
     Ok(())
 }
 
@@ -375,6 +973,110 @@ async fn select_pdf_file(app: tauri::AppHandle) -> Option<String> {
     file_path.map(|path| path.to_string())
 }
 
+// ==========================================
+// PRESENTACIONES (PowerPoint / Impress vía LibreOffice)
+// ==========================================
+const CONFIG_SOFFICE: &str = "soffice_path";
+
+#[tauri::command]
+async fn select_presentation_file(app: tauri::AppHandle) -> Option<String> {
+    use tauri_plugin_dialog::DialogExt;
+    let file_path = app.dialog().file().add_filter("Presentaciones", &["pptx", "ppt", "ppsx", "pps", "odp", "key"]).blocking_pick_file();
+    file_path.map(|path| path.to_string())
+}
+
+// Convierte la presentación a PDF con LibreOffice y la pasa por el mismo motor que los PDFs
+#[tauri::command]
+async fn add_presentation(app: tauri::AppHandle, nombre: String, ruta: String, opciones: Option<PdfRenderOpciones>, state: State<'_, AppState>) -> Result<(), String> {
+    let soffice = {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        buscar_soffice(leer_config(&conn, CONFIG_SOFFICE))?
+    };
+
+    let _ = app.emit("pdf-progress", serde_json::json!({ "current": 0, "total": 0, "status": "Convirtiendo presentación con LibreOffice..." }));
+
+    // El PDF convertido se conserva para poder re-renderizar después
+    let app_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let carpeta_pdf = app_dir.join("presentaciones").join(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis().to_string());
+    fs::create_dir_all(&carpeta_pdf).map_err(|e| e.to_string())?;
+    let ruta_pdf = convertir_a_pdf(&soffice, &ruta, &carpeta_pdf, &app_dir.join("libreoffice_perfil"))?;
+
+    importar_pdf(&app, &nombre, &ruta_pdf.to_string_lossy(), opciones, &state)?;
+    Ok(())
+}
+
+// Ruta configurada o, si no hay, las ubicaciones habituales de LibreOffice
+fn buscar_soffice(configurada: Option<String>) -> Result<PathBuf, String> {
+    if let Some(ruta) = configurada.filter(|r| !r.trim().is_empty()) {
+        let ruta = PathBuf::from(ruta.trim());
+        return if ruta.is_file() { Ok(ruta) } else { Err(format!("No se encontró LibreOffice en la ruta configurada: {}", ruta.display())) };
+    }
+
+    let candidatos = [
+        "C:\\Program Files\\LibreOffice\\program\\soffice.exe",
+        "C:\\Program Files (x86)\\LibreOffice\\program\\soffice.exe",
+        "/usr/bin/soffice",
+        "/usr/bin/libreoffice",
+        "/usr/local/bin/soffice",
+        "/snap/bin/libreoffice",
+        "/Applications/LibreOffice.app/Contents/MacOS/soffice",
+    ];
+    if let Some(ruta) = candidatos.iter().map(PathBuf::from).find(|p| p.is_file()) {
+        return Ok(ruta);
+    }
+
+    // Último intento: que esté en el PATH
+    let en_path = if cfg!(target_os = "windows") { "soffice.exe" } else { "soffice" };
+    if std::process::Command::new(en_path).arg("--version").output().is_ok() {
+        return Ok(PathBuf::from(en_path));
+    }
+
+    Err("No se encontró LibreOffice (soffice). Instálelo desde libreoffice.org o configure la ruta de soffice en los ajustes.".to_string())
+}
+
+fn convertir_a_pdf(soffice: &std::path::Path, ruta: &str, carpeta_salida: &std::path::Path, perfil: &std::path::Path) -> Result<PathBuf, String> {
+    // Un perfil propio evita conflictos si el usuario tiene LibreOffice abierto
+    let perfil_url = format!("file:///{}", perfil.to_string_lossy().replace('\\', "/").trim_start_matches('/'));
+    let salida = std::process::Command::new(soffice)
+        .arg(format!("-env:UserInstallation={}", perfil_url))
+        .args(["--headless", "--convert-to", "pdf", "--outdir"])
+        .arg(carpeta_salida)
+        .arg(ruta)
+        .output()
+        .map_err(|e| format!("No se pudo ejecutar LibreOffice ({}): {}", soffice.display(), e))?;
+
+    let stem = std::path::Path::new(ruta).file_stem().ok_or("Nombre de archivo inválido")?;
+    let ruta_pdf = carpeta_salida.join(stem).with_extension("pdf");
+    if !salida.status.success() || !ruta_pdf.exists() {
+        return Err(format!("LibreOffice no pudo convertir la presentación: {}", String::from_utf8_lossy(&salida.stderr).trim()));
+    }
+    Ok(ruta_pdf)
+}
+
+// ==========================================
+// CONFIGURACIÓN (clave/valor en multimedia.db)
+// ==========================================
+fn leer_config(conn: &Connection, clave: &str) -> Option<String> {
+    conn.query_row("SELECT valor FROM configuracion WHERE clave = ?", params![clave], |row| row.get(0)).optional().ok().flatten()
+}
+
+fn guardar_config(conn: &Connection, clave: &str, valor: &str) -> rusqlite::Result<()> {
+    conn.execute("INSERT INTO configuracion (clave, valor) VALUES (?, ?) ON CONFLICT(clave) DO UPDATE SET valor = excluded.valor", params![clave, valor])?;
+    Ok(())
+}
+
+#[tauri::command]
+fn get_config(clave: String, state: State<AppState>) -> Result<Option<String>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    Ok(leer_config(&conn, &clave))
+}
+
+#[tauri::command]
+fn set_config(clave: String, valor: String, state: State<AppState>) -> Result<(), String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    guardar_config(&conn, &clave, &valor).map_err(|e| e.to_string())
+}
+
 
 /*fn setup_db(db_name: &str) -> Connection {
     let conn = Connection::open(db_name).expect(&format!("No se pudo abrir {}", db_name));
@@ -388,12 +1090,42 @@ fn setup_multimedia_db(path: PathBuf) -> Connection {
     // TABLA DE IMÁGENES
     conn.execute("CREATE TABLE IF NOT EXISTS imagenes (id INTEGER PRIMARY KEY AUTOINCREMENT, nombre TEXT NOT NULL, ruta TEXT NOT NULL, aspecto TEXT DEFAULT 'contain')", []).unwrap();
     let _ = conn.execute("ALTER TABLE imagenes ADD COLUMN aspecto TEXT DEFAULT 'contain'", []);
+    // Procesado al importar y edición no destructiva (las imágenes anteriores quedan sin procesar)
+    for columna in ["ruta_proyeccion TEXT", "corregir_orientacion INTEGER DEFAULT 0", "reducir INTEGER DEFAULT 0", "rotacion INTEGER DEFAULT 0", "recorte_x REAL", "recorte_y REAL", "recorte_ancho REAL", "recorte_alto REAL"] {
+        let _ = conn.execute(&format!("ALTER TABLE imagenes ADD COLUMN {}", columna), []);
+    }
     
     // TABLA DE VIDEOS (Agregada la columna de bucle)
-    conn.execute("CREATE TABLE IF NOT EXISTS videos (id INTEGER PRIMARY KEY AUTOINCREMENT, nombre TEXT NOT NULL, ruta TEXT NOT NULL, bucle INTEGER DEFAULT 0)", []).unwrap();
+    conn.execute("CREATE TABLE IF NOT EXISTS videos (id INTEGER PRIMARY KEY AUTOINCREMENT, nombre TEXT NOT NULL, ruta TEXT NOT NULL, bucle INTEGER DEFAULT 0, duracion REAL, ancho INTEGER, alto INTEGER, codec TEXT, tiene_audio INTEGER, poster TEXT)", []).unwrap();
     let _ = conn.execute("ALTER TABLE videos ADD COLUMN bucle INTEGER DEFAULT 0", []);
+    for columna in ["duracion REAL", "ancho INTEGER", "alto INTEGER", "codec TEXT", "tiene_audio INTEGER", "poster TEXT"] {
+        let _ = conn.execute(&format!("ALTER TABLE videos ADD COLUMN {}", columna), []);
+    }
     
-    conn.execute("CREATE TABLE IF NOT EXISTS pdfs (id INTEGER PRIMARY KEY AUTOINCREMENT, nombre TEXT NOT NULL, ruta TEXT NOT NULL)", []).unwrap();
+    // TABLA DE PDFs (la ruta apunta a la carpeta de páginas; se guardan el original y las opciones de renderizado)
+    conn.execute("CREATE TABLE IF NOT EXISTS pdfs (id INTEGER PRIMARY KEY AUTOINCREMENT, nombre TEXT NOT NULL, ruta TEXT NOT NULL, ruta_original TEXT, ancho INTEGER, alto INTEGER, pagina_inicio INTEGER, pagina_fin INTEGER, formato TEXT DEFAULT 'jpg', calidad INTEGER DEFAULT 90, fondo TEXT DEFAULT '#FFFFFF', paginas INTEGER DEFAULT 0)", []).unwrap();
+    for columna in ["ruta_original TEXT", "ancho INTEGER", "alto INTEGER", "pagina_inicio INTEGER", "pagina_fin INTEGER", "formato TEXT DEFAULT 'jpg'", "calidad INTEGER DEFAULT 90", "fondo TEXT DEFAULT '#FFFFFF'", "paginas INTEGER DEFAULT 0"] {
+        let _ = conn.execute(&format!("ALTER TABLE pdfs ADD COLUMN {}", columna), []);
+    }
+
+    // PÁGINAS DE CADA PDF (imagen generada, medidas y texto extraído)
+    conn.execute("CREATE TABLE IF NOT EXISTS pdf_paginas (id INTEGER PRIMARY KEY AUTOINCREMENT, pdf_id INTEGER NOT NULL, numero INTEGER NOT NULL, archivo TEXT NOT NULL, ancho INTEGER, alto INTEGER, ancho_pt REAL, alto_pt REAL, texto TEXT)", []).unwrap();
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_pdf_paginas_pdf ON pdf_paginas(pdf_id, numero)", []).unwrap();
+
+    // TABLAS DE AUDIO Y LISTAS DE REPRODUCCIÓN
+    audio::crear_tablas(&conn);
+
+    // CARPETAS, ETIQUETAS Y ORDEN MANUAL (para todas las tablas de medios)
+    biblioteca::crear_tablas(&conn);
+    carpetas_vigiladas::crear_tablas(&conn);
+    carrusel::crear_tablas(&conn);
+    temas::crear_tablas(&conn);
+    salidas::crear_tablas(&conn);
+    obs::crear_tablas(&conn);
+    avisos::crear_tablas(&conn);
+
+    // CONFIGURACIÓN GENERAL (clave/valor)
+    conn.execute("CREATE TABLE IF NOT EXISTS configuracion (clave TEXT PRIMARY KEY, valor TEXT NOT NULL)", []).unwrap();
 
     conn
 }
@@ -517,6 +1249,7 @@ fn main() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_fs::init()) 
+        .plugin(tauri_plugin_global_shortcut::Builder::new().with_handler(atajos::al_pulsar).build())
         .setup(|app| {
             let handle = app.handle();
             
@@ -529,6 +1262,15 @@ fn main() {
                 cantos_db: Mutex::new(setup_db(c_path)),
                 biblias_db: Mutex::new(setup_db(b_path)),
                 multimedia_db: Mutex::new(setup_multimedia_db(m_path)),
+                video_estado: Mutex::new(None),
+                vigilante: Mutex::new(None),
+                carrusel: Mutex::new(None),
+                proyeccion: Mutex::new(None),
+                superposicion: Mutex::new(None),
+                avisos: Mutex::new(avisos::ColaAvisos::default()),
+                temporizadores: Mutex::new(temporizadores::Temporizadores::new()),
+                pantalla: Mutex::new(pantalla::EstadoPantalla::default()),
+                obs: Mutex::new(None),
             };
 
             {
@@ -543,9 +1285,18 @@ fn main() {
                     "CREATE INDEX IF NOT EXISTS idx_cantos_titulo ON cantos(titulo);
                      CREATE INDEX IF NOT EXISTS idx_diapositivas_canto ON diapositivas(canto_id);"
                 ).unwrap_or_else(|e| println!("Nota índices cantos: {}", e));
+
+                temas::migrar_referencias(&conn_cantos, &conn_biblia);
             }
             
             app.manage(app_state);
+
+            // Vigilancia de carpetas: necesita el estado ya registrado
+            carpetas_vigiladas::iniciar(handle.clone());
+            salidas::iniciar(handle.clone());
+            superposicion::iniciar(handle.clone());
+            temporizadores::iniciar(handle.clone());
+            obs::iniciar(handle.clone());
             Ok(())
         })
 
@@ -557,7 +1308,73 @@ fn main() {
             get_single_verse,
             trigger_projection,
             select_background_image,
-            trigger_style_update,
+            temas::trigger_style_update,
+            temas::get_projector_styles,
+            temas::get_all_themes,
+            temas::add_theme,
+            temas::update_theme,
+            temas::delete_theme,
+            temas::set_song_theme,
+            temas::set_version_theme,
+            temas::set_song_style,
+            temas::get_song_style,
+            temas::set_category_theme,
+            temas::get_effective_style,
+            maquetado::fit_text,
+            maquetado::check_song_overflow,
+            renderizado::render_slide_preview,
+            renderizado::export_slides,
+            renderizado::export_song_images,
+            cancionero::export_songbook,
+            salidas::get_monitors,
+            salidas::get_output_assignments,
+            salidas::assign_output_monitor,
+            salidas::reset_output_monitor,
+            salidas::get_audience_outputs,
+            salidas::save_audience_output,
+            salidas::delete_audience_output,
+            salidas::open_audience_output,
+            salidas::close_audience_output,
+            superposicion::get_overlay_config,
+            superposicion::set_overlay_config,
+            superposicion::get_overlay_url,
+            obs::get_obs_config,
+            obs::set_obs_config,
+            obs::test_obs_connection,
+            obs::get_obs_scenes,
+            obs::run_obs_action,
+            obs::get_obs_rules,
+            obs::save_obs_rule,
+            obs::delete_obs_rule,
+            avisos::show_alert,
+            avisos::show_alert_template,
+            avisos::dismiss_alert,
+            avisos::cancel_alert,
+            avisos::clear_alerts,
+            avisos::get_alerts_state,
+            avisos::get_alert_templates,
+            avisos::save_alert_template,
+            avisos::delete_alert_template,
+            temporizadores::create_timer,
+            temporizadores::start_timer,
+            temporizadores::pause_timer,
+            temporizadores::reset_timer,
+            temporizadores::delete_timer,
+            temporizadores::get_timers,
+            temporizadores::subscribe_timer,
+            temporizadores::unsubscribe_timer,
+            pantalla::set_screen_mode,
+            pantalla::get_live_state,
+            pantalla::set_logo_image,
+            pantalla::get_logo_image,
+            transiciones::get_media_transitions,
+            transiciones::set_media_transitions,
+            protocolo::get_protocol_version,
+            atajos::get_shortcuts,
+            atajos::set_shortcut,
+            atajos::reset_shortcuts,
+            temas::set_default_theme,
+            temas::get_default_themes,
             get_all_cantos,
             get_canto_diapositivas,
             add_canto,
@@ -567,16 +1384,67 @@ fn main() {
             add_image_db,
             delete_image_db,
             update_image_aspect,
+            update_image_edit,
             get_all_videos,
             add_video_db,
             delete_video_db,
             update_video_loop, // <--- Comando Registrado
+            refresh_video_metadata,
             select_video_file,
             trigger_video_control,
+            report_video_state,
+            get_video_state,
             get_all_pdfs,
             add_pdf_db,
+            rerender_pdf,
+            get_pdf_pages,
+            search_pdf_pages,
             delete_pdf_db,
             select_pdf_file,
+            select_presentation_file,
+            add_presentation,
+            get_config,
+            set_config,
+            audio::get_all_audios,
+            audio::add_audio_db,
+            audio::delete_audio_db,
+            audio::select_audio_file,
+            audio::get_all_playlists,
+            audio::add_playlist,
+            audio::update_playlist,
+            audio::delete_playlist,
+            audio::get_playlist_audios,
+            audio::set_playlist_audios,
+            audio::play_playlist,
+            audio::play_audio,
+            audio::trigger_audio_control,
+            biblioteca::get_folders,
+            biblioteca::add_folder,
+            biblioteca::rename_folder,
+            biblioteca::delete_folder,
+            biblioteca::move_media,
+            biblioteca::reorder_media,
+            biblioteca::rename_media,
+            biblioteca::get_all_tags,
+            biblioteca::tag_media,
+            biblioteca::untag_media,
+            biblioteca::delete_tag,
+            carpetas_vigiladas::get_watch_folders,
+            carpetas_vigiladas::select_watch_folder,
+            carpetas_vigiladas::add_watch_folder,
+            carpetas_vigiladas::delete_watch_folder,
+            carpetas_vigiladas::rescan_watch_folders,
+            carrusel::get_all_slideshows,
+            carrusel::add_slideshow,
+            carrusel::update_slideshow,
+            carrusel::delete_slideshow,
+            carrusel::get_slideshow_items,
+            carrusel::set_slideshow_items,
+            carrusel::start_slideshow,
+            carrusel::stop_slideshow,
+            carrusel::slideshow_next,
+            carrusel::slideshow_prev,
+            carrusel::pause_slideshow,
             export_cantos,
             import_cantos
         ])
@@ -0,0 +1,236 @@
+// ==========================================
+// MAQUETADO DE TEXTO (auto-ajuste con métricas reales de la fuente)
+// ==========================================
+// Reproduce el ajuste del proyector (búsqueda binaria entre 20 y 250 px,
+// interlineado 1.25, `whitespace-pre-line` y `break-words`) sin depender del webview.
+use crate::temas::{self, Estilo};
+use crate::{resolucion_proyector, AppState};
+use ab_glyph::{Font, FontArc, PxScale, ScaleFont};
+use rusqlite::params;
+use serde::Serialize;
+use tauri::State;
+
+// DejaVu Sans Bold: la misma familia sans en negrita que usa el proyector por defecto
+const FUENTE_INCLUIDA: &[u8] = include_bytes!("../fonts/DejaVuSans-Bold.ttf");
+
+pub const TAMANO_MIN: f32 = 20.0;
+pub const TAMANO_MAX: f32 = 250.0;
+pub const INTERLINEADO: f32 = 1.25;
+const RELLENO: f32 = 32.0;     // px-8 / py-8 del contenedor de texto
+const PIE_REFERENCIA: f32 = 64.0; // Referencia bíblica (text-5xl) + su margen
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Maquetado {
+    pub tamano: f32,         // Tamaño de letra elegido (px)
+    pub lineas: Vec<String>, // Texto ya partido en líneas
+    pub ancho: f32,          // Medidas que ocupa el bloque de texto
+    pub alto: f32,
+    pub desborda: bool,      // No cabe ni con el tamaño mínimo (o con el tamaño fijo del tema)
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AvisoDiapositiva {
+    orden: i32,
+    tamano: f32,
+    lineas: usize,
+    desborda: bool,
+}
+
+// Si `fuente` es la ruta de un archivo .ttf/.otf se usa ese; si no, la fuente incluida
+pub fn cargar_fuente(fuente: &str) -> FontArc {
+    let ruta = std::path::Path::new(fuente);
+    let es_archivo = ruta.extension().is_some_and(|e| e.eq_ignore_ascii_case("ttf") || e.eq_ignore_ascii_case("otf"));
+    if es_archivo {
+        if let Some(font) = std::fs::read(ruta).ok().and_then(|datos| FontArc::try_from_vec(datos).ok()) {
+            return font;
+        }
+    }
+    FontArc::try_from_slice(FUENTE_INCLUIDA).expect("La fuente incluida es válida")
+}
+
+// Escala de ab_glyph equivalente a un `font-size` de CSS en píxeles
+pub fn escala(font: &FontArc, tamano: f32) -> PxScale {
+    let unidades = font.units_per_em().unwrap_or(1000.0);
+    PxScale::from(tamano * font.height_unscaled() / unidades)
+}
+
+pub fn ancho_texto(font: &FontArc, tamano: f32, texto: &str) -> f32 {
+    let escalada = font.as_scaled(escala(font, tamano));
+    let mut ancho = 0.0;
+    let mut anterior = None;
+    for c in texto.chars() {
+        let id = escalada.glyph_id(c);
+        if let Some(prev) = anterior { ancho += escalada.kern(prev, id); }
+        ancho += escalada.h_advance(id);
+        anterior = Some(id);
+    }
+    ancho
+}
+
+// Parte el texto en líneas que entren en `ancho_max`. Respeta los saltos de línea
+// y corta por letras las palabras que no entran solas (como `break-words`).
+pub fn partir_lineas(font: &FontArc, tamano: f32, texto: &str, ancho_max: f32) -> Vec<String> {
+    let mut lineas = Vec::new();
+    for parrafo in texto.split('\n') {
+        let mut actual = String::new();
+        for palabra in parrafo.split_whitespace() {
+            let candidata = if actual.is_empty() { palabra.to_string() } else { format!("{} {}", actual, palabra) };
+            if ancho_texto(font, tamano, &candidata) <= ancho_max {
+                actual = candidata;
+                continue;
+            }
+            if !actual.is_empty() {
+                lineas.push(std::mem::take(&mut actual));
+            }
+            // La palabra sola tampoco entra: se corta por letras
+            for c in palabra.chars() {
+                actual.push(c);
+                if actual.chars().count() > 1 && ancho_texto(font, tamano, &actual) > ancho_max {
+                    actual.pop();
+                    lineas.push(std::mem::take(&mut actual));
+                    actual.push(c);
+                }
+            }
+        }
+        lineas.push(actual);
+    }
+    lineas
+}
+
+fn medir(font: &FontArc, tamano: f32, texto: &str, caja: (f32, f32)) -> Maquetado {
+    let lineas = partir_lineas(font, tamano, texto, caja.0);
+    let ancho = lineas.iter().map(|l| ancho_texto(font, tamano, l)).fold(0.0, f32::max);
+    let alto = lineas.len() as f32 * tamano * INTERLINEADO;
+    Maquetado { tamano, desborda: alto > caja.1 || ancho > caja.0, lineas, ancho, alto }
+}
+
+// El mayor tamaño entero que entra en la caja (o el fijo, si el tema lo define)
+pub fn ajustar(font: &FontArc, texto: &str, caja: (f32, f32), tamano_fijo: Option<f32>) -> Maquetado {
+    if let Some(tamano) = tamano_fijo {
+        return medir(font, tamano, texto, caja);
+    }
+    let (mut min, mut max) = (TAMANO_MIN as u32, TAMANO_MAX as u32);
+    let mut mejor = None;
+    while min <= max {
+        let medio = (min + max) / 2;
+        let resultado = medir(font, medio as f32, texto, caja);
+        if resultado.desborda {
+            max = medio - 1;
+        } else {
+            mejor = Some(resultado);
+            min = medio + 1;
+        }
+    }
+    mejor.unwrap_or_else(|| medir(font, TAMANO_MIN, texto, caja))
+}
+
+// Área útil para el texto en una pantalla de `resolucion` con los márgenes del estilo
+pub fn caja_texto(estilo: &Estilo, resolucion: (u32, u32), con_referencia: bool) -> (f32, f32) {
+    let m = estilo.margenes;
+    let ancho = resolucion.0 as f32 * (1.0 - (m.left + m.right) / 100.0) - RELLENO * 2.0;
+    let mut alto = resolucion.1 as f32 * (1.0 - (m.top + m.bottom) / 100.0) - RELLENO * 2.0;
+    if con_referencia { alto -= PIE_REFERENCIA; }
+    (ancho.max(1.0), alto.max(1.0))
+}
+
+// ==========================================
+// COMANDOS DE MAQUETADO
+// ==========================================
+// Sin estilo se usa el estilo en vivo de los versículos; sin medidas, la resolución del proyector
+#[tauri::command]
+pub fn fit_text(app: tauri::AppHandle, texto: String, estilo: Option<Estilo>, ancho: Option<u32>, alto: Option<u32>, con_referencia: Option<bool>, state: State<AppState>) -> Result<Maquetado, String> {
+    let estilo = match estilo {
+        Some(estilo) => estilo,
+        None => temas::resolver_estilo(&state, &serde_json::json!({}), None)?.unwrap_or_default(),
+    };
+    let resolucion = match (ancho, alto) {
+        (Some(ancho), Some(alto)) => (ancho, alto),
+        _ => resolucion_proyector(&app),
+    };
+    let font = cargar_fuente(&estilo.fuente);
+    Ok(ajustar(&font, &texto, caja_texto(&estilo, resolucion, con_referencia.unwrap_or(false)), estilo.tamano))
+}
+
+// Avisa qué diapositivas de un canto no caben con su tema actual.
+// `letra` permite revisar el texto que se está editando antes de guardarlo (mismo formato que add_canto).
+#[tauri::command]
+pub fn check_song_overflow(app: tauri::AppHandle, canto_id: Option<i32>, letra: Option<String>, state: State<AppState>) -> Result<Vec<AvisoDiapositiva>, String> {
+    let diapositivas: Vec<(i32, String)> = match (letra, canto_id) {
+        (Some(letra), _) => letra.split("\n\n").map(str::trim).filter(|e| !e.is_empty()).enumerate().map(|(i, e)| (i as i32 + 1, e.to_string())).collect(),
+        (None, Some(id)) => {
+            let conn = state.cantos_db.lock().map_err(|e| e.to_string())?;
+            let mut stmt = conn.prepare("SELECT orden, texto FROM diapositivas WHERE canto_id = ? ORDER BY orden").map_err(|e| e.to_string())?;
+            let iter = stmt.query_map(params![id], |row| Ok((row.get(0)?, row.get(1)?))).map_err(|e| e.to_string())?;
+            iter.filter_map(Result::ok).collect()
+        }
+        (None, None) => return Err("Indique el canto o la letra a revisar".to_string()),
+    };
+
+    let payload = serde_json::json!({ "capitulo": 0, "canto_id": canto_id });
+    let estilo = temas::resolver_estilo(&state, &payload, None)?.unwrap_or_default();
+    let font = cargar_fuente(&estilo.fuente);
+    let caja = caja_texto(&estilo, resolucion_proyector(&app), false);
+
+    Ok(diapositivas.into_iter().map(|(orden, texto)| {
+        let m = ajustar(&font, &texto, caja, estilo.tamano);
+        AvisoDiapositiva { orden, tamano: m.tamano, lineas: m.lineas.len(), desborda: m.desborda }
+    }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fuente() -> FontArc {
+        cargar_fuente("")
+    }
+
+    #[test]
+    fn texto_corto_usa_el_tamano_maximo() {
+        let m = ajustar(&fuente(), "Amén", (1856.0, 1016.0), None);
+        assert_eq!(m.tamano, TAMANO_MAX);
+        assert!(!m.desborda);
+    }
+
+    #[test]
+    fn texto_que_no_entra_queda_en_el_minimo_y_desborda() {
+        let texto = "Porque de tal manera amó Dios al mundo ".repeat(40);
+        let m = ajustar(&fuente(), &texto, (400.0, 200.0), None);
+        assert_eq!(m.tamano, TAMANO_MIN);
+        assert!(m.desborda);
+    }
+
+    #[test]
+    fn busqueda_binaria_elige_el_mayor_tamano_que_entra() {
+        let font = fuente();
+        let caja = (1200.0, 600.0);
+        let texto = "Porque de tal manera amó Dios al mundo, que ha dado a su Hijo unigénito";
+        let m = ajustar(&font, texto, caja, None);
+        assert!((TAMANO_MIN..TAMANO_MAX).contains(&m.tamano));
+        assert!(!m.desborda);
+        assert!(m.alto <= caja.1 && m.ancho <= caja.0);
+        assert!(medir(&font, m.tamano + 1.0, texto, caja).desborda);
+    }
+
+    #[test]
+    fn tamano_fijo_no_se_ajusta() {
+        let m = ajustar(&fuente(), "Santo, santo, santo", (300.0, 100.0), Some(120.0));
+        assert_eq!(m.tamano, 120.0);
+        assert!(m.desborda);
+    }
+
+    #[test]
+    fn palabras_largas_se_cortan_por_letras_sin_romper_multibyte() {
+        let font = fuente();
+        let lineas = partir_lineas(&font, 40.0, "Ñandúñandúñandúñandúñandú", 150.0);
+        assert!(lineas.len() > 1);
+        assert_eq!(lineas.concat(), "Ñandúñandúñandúñandúñandú");
+        assert!(lineas.iter().all(|l| ancho_texto(&font, 40.0, l) <= 150.0));
+    }
+
+    #[test]
+    fn respeta_los_saltos_de_linea() {
+        let lineas = partir_lineas(&fuente(), 40.0, "Gloria\n\nAleluya", 2000.0);
+        assert_eq!(lineas, vec!["Gloria", "", "Aleluya"]);
+    }
+}
@@ -0,0 +1,560 @@
+// ==========================================
+// OBS WEBSOCKET (cambio de escenas según lo que se proyecta)
+// ==========================================
+// Cliente del protocolo obs-websocket v5. Las reglas dicen qué hacer en OBS
+// cuando sale al aire cierto contenido (un video, un canto, el sermón...).
+// Los disparos pasan por un único hilo en orden de llegada, así dos cambios
+// seguidos no llegan a OBS al revés. Cada disparo abre su propia conexión: OBS
+// corre en la misma red y así no hay que mantener ni reconectar un socket abierto.
+use crate::{guardar_config, leer_config, AppState};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc;
+use std::time::Duration;
+use tauri::{Emitter, Manager, State};
+use tungstenite::{Message, WebSocket};
+
+const CONFIG_OBS: &str = "obs";
+const TIEMPO_ESPERA: Duration = Duration::from_secs(3);
+
+// Códigos de operación del protocolo
+const OP_HELLO: u64 = 0;
+const OP_IDENTIFY: u64 = 1;
+const OP_IDENTIFIED: u64 = 2;
+const OP_REQUEST: u64 = 6;
+const OP_REQUEST_RESPONSE: u64 = 7;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ConfigObs {
+    pub activo: bool,
+    pub host: String,
+    pub puerto: u16,
+    pub contrasena: String, // "" = OBS sin autenticación
+}
+
+impl Default for ConfigObs {
+    fn default() -> Self {
+        ConfigObs { activo: false, host: "127.0.0.1".to_string(), puerto: 4455, contrasena: String::new() }
+    }
+}
+
+// Tipo de contenido que sale al aire
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoContenido { Biblia, Canto, Imagen, Video, Pdf }
+
+// Todas las condiciones presentes deben cumplirse
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Condicion {
+    pub tipo: Option<TipoContenido>,
+    pub canto_id: Option<i32>,
+    pub contiene: Option<String>, // En el libro / título del canto o en la ruta del archivo (sin distinguir mayúsculas)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "accion", rename_all = "snake_case")]
+pub enum AccionObs {
+    Escena { escena: String },
+    Fuente { escena: String, fuente: String, visible: bool },
+    IniciarGrabacion,
+    DetenerGrabacion,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReglaObs {
+    pub id: Option<i32>, // None al crearla
+    pub nombre: String,
+    pub activa: bool,
+    pub condicion: Condicion,
+    pub acciones: Vec<AccionObs>,
+}
+
+impl ReglaObs {
+    fn validar(&self) -> Result<(), String> {
+        if self.nombre.trim().is_empty() { return Err("La regla necesita un nombre".to_string()); }
+        let c = &self.condicion;
+        if c.tipo.is_none() && c.canto_id.is_none() && c.contiene.as_deref().is_none_or(|t| t.trim().is_empty()) {
+            return Err("La regla necesita al menos una condición".to_string());
+        }
+        if self.acciones.is_empty() { return Err("La regla necesita al menos una acción".to_string()); }
+        for accion in &self.acciones {
+            match accion {
+                AccionObs::Escena { escena } if escena.trim().is_empty() => return Err("Indique la escena".to_string()),
+                AccionObs::Fuente { escena, fuente, .. } if escena.trim().is_empty() || fuente.trim().is_empty() => return Err("Indique la escena y la fuente".to_string()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn crear_tablas(conn: &Connection) {
+    conn.execute("CREATE TABLE IF NOT EXISTS obs_reglas (id INTEGER PRIMARY KEY AUTOINCREMENT, nombre TEXT NOT NULL, activa INTEGER DEFAULT 1, condicion TEXT NOT NULL, acciones TEXT NOT NULL)", []).unwrap();
+}
+
+fn leer_configuracion(conn: &Connection) -> ConfigObs {
+    leer_config(conn, CONFIG_OBS).and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default()
+}
+
+fn read_regla(row: &rusqlite::Row) -> rusqlite::Result<ReglaObs> {
+    let condicion: String = row.get(3)?;
+    let acciones: String = row.get(4)?;
+    Ok(ReglaObs {
+        id: row.get(0)?,
+        nombre: row.get(1)?,
+        activa: row.get::<_, i32>(2)? != 0,
+        condicion: serde_json::from_str(&condicion).unwrap_or_default(),
+        acciones: serde_json::from_str(&acciones).unwrap_or_default(),
+    })
+}
+
+fn listar_reglas(conn: &Connection) -> rusqlite::Result<Vec<ReglaObs>> {
+    let mut stmt = conn.prepare("SELECT id, nombre, activa, condicion, acciones FROM obs_reglas ORDER BY id")?;
+    let iter = stmt.query_map([], read_regla)?;
+    Ok(iter.filter_map(Result::ok).collect())
+}
+
+// ==========================================
+// CLIENTE OBS WEBSOCKET v5
+// ==========================================
+pub struct ClienteObs {
+    socket: WebSocket<TcpStream>,
+    siguiente_id: u64,
+}
+
+// Respuesta al desafío: base64(sha256(base64(sha256(contraseña + sal)) + desafío))
+fn autenticacion(contrasena: &str, sal: &str, desafio: &str) -> String {
+    let secreto = BASE64.encode(Sha256::digest(format!("{}{}", contrasena, sal)));
+    BASE64.encode(Sha256::digest(format!("{}{}", secreto, desafio)))
+}
+
+impl ClienteObs {
+    pub fn conectar(config: &ConfigObs) -> Result<ClienteObs, String> {
+        let direccion = (config.host.as_str(), config.puerto).to_socket_addrs()
+            .map_err(|e| format!("Dirección de OBS inválida: {}", e))?
+            .next()
+            .ok_or("Dirección de OBS inválida")?;
+        let stream = TcpStream::connect_timeout(&direccion, TIEMPO_ESPERA).map_err(|e| format!("No se pudo conectar con OBS: {}", e))?;
+        stream.set_read_timeout(Some(TIEMPO_ESPERA)).map_err(|e| e.to_string())?;
+        stream.set_write_timeout(Some(TIEMPO_ESPERA)).map_err(|e| e.to_string())?;
+        let url = format!("ws://{}:{}", config.host, config.puerto);
+        let (socket, _) = tungstenite::client(url.as_str(), stream).map_err(|e| format!("OBS rechazó la conexión: {}", e))?;
+
+        let mut cliente = ClienteObs { socket, siguiente_id: 1 };
+        let hola = cliente.recibir(OP_HELLO)?;
+        let mut identificar = serde_json::json!({ "rpcVersion": 1, "eventSubscriptions": 0 });
+        if let Some(auth) = hola["authentication"].as_object() {
+            if config.contrasena.is_empty() { return Err("OBS pide contraseña".to_string()); }
+            let sal = auth.get("salt").and_then(|v| v.as_str()).unwrap_or("");
+            let desafio = auth.get("challenge").and_then(|v| v.as_str()).unwrap_or("");
+            identificar["authentication"] = serde_json::json!(autenticacion(&config.contrasena, sal, desafio));
+        }
+        cliente.enviar(OP_IDENTIFY, identificar)?;
+        cliente.recibir(OP_IDENTIFIED)?;
+        Ok(cliente)
+    }
+
+    fn enviar(&mut self, op: u64, d: serde_json::Value) -> Result<(), String> {
+        let mensaje = serde_json::json!({ "op": op, "d": d }).to_string();
+        self.socket.send(Message::Text(mensaje)).map_err(|e| format!("Error enviando a OBS: {}", e))
+    }
+
+    // Espera el siguiente mensaje con el código indicado y devuelve su campo `d`
+    fn recibir(&mut self, op: u64) -> Result<serde_json::Value, String> {
+        loop {
+            match self.socket.read() {
+                Ok(Message::Text(texto)) => {
+                    let mut mensaje: serde_json::Value = serde_json::from_str(&texto).map_err(|e| e.to_string())?;
+                    if mensaje["op"].as_u64() == Some(op) {
+                        return Ok(mensaje["d"].take());
+                    }
+                }
+                // 4009 = autenticación fallida
+                Ok(Message::Close(Some(cierre))) if u16::from(cierre.code) == 4009 => return Err("Contraseña de OBS incorrecta".to_string()),
+                Ok(Message::Close(cierre)) => return Err(format!("OBS cerró la conexión{}", cierre.map(|c| format!(": {}", c.reason)).unwrap_or_default())),
+                Ok(_) => {}
+                Err(e) => return Err(format!("Error leyendo de OBS: {}", e)),
+            }
+        }
+    }
+
+    // Envía una petición y devuelve `responseData`
+    pub fn peticion(&mut self, tipo: &str, datos: serde_json::Value) -> Result<serde_json::Value, String> {
+        let id = self.siguiente_id.to_string();
+        self.siguiente_id += 1;
+        self.enviar(OP_REQUEST, serde_json::json!({ "requestType": tipo, "requestId": id, "requestData": datos }))?;
+        loop {
+            let mut respuesta = self.recibir(OP_REQUEST_RESPONSE)?;
+            if respuesta["requestId"].as_str() != Some(id.as_str()) { continue; }
+            let estado = &respuesta["requestStatus"];
+            if estado["result"].as_bool() != Some(true) {
+                let detalle = estado["comment"].as_str().map(str::to_string).unwrap_or_else(|| format!("código {}", estado["code"]));
+                return Err(format!("OBS no pudo hacer '{}': {}", tipo, detalle));
+            }
+            return Ok(respuesta["responseData"].take());
+        }
+    }
+
+    pub fn ejecutar(&mut self, accion: &AccionObs) -> Result<(), String> {
+        match accion {
+            AccionObs::Escena { escena } => {
+                self.peticion("SetCurrentProgramScene", serde_json::json!({ "sceneName": escena }))?;
+            }
+            AccionObs::Fuente { escena, fuente, visible } => {
+                let item = self.peticion("GetSceneItemId", serde_json::json!({ "sceneName": escena, "sourceName": fuente }))?;
+                self.peticion("SetSceneItemEnabled", serde_json::json!({ "sceneName": escena, "sceneItemId": item["sceneItemId"], "sceneItemEnabled": visible }))?;
+            }
+            // Solo si hace falta: OBS responde con error si ya está grabando (o detenido)
+            AccionObs::IniciarGrabacion | AccionObs::DetenerGrabacion => {
+                let grabando = self.peticion("GetRecordStatus", serde_json::json!({}))?["outputActive"].as_bool().unwrap_or(false);
+                match accion {
+                    AccionObs::IniciarGrabacion if !grabando => { self.peticion("StartRecord", serde_json::json!({}))?; }
+                    AccionObs::DetenerGrabacion if grabando => { self.peticion("StopRecord", serde_json::json!({}))?; }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn cerrar(mut self) {
+        let _ = self.socket.close(None);
+        let _ = self.socket.flush();
+    }
+}
+
+// ==========================================
+// REGLAS (disparadas desde trigger_projection)
+// ==========================================
+fn tipo_de(payload: &serde_json::Value) -> TipoContenido {
+    match payload.get("tipo").and_then(|t| t.as_str()) {
+        Some("imagen") => TipoContenido::Imagen,
+        Some("video") => TipoContenido::Video,
+        Some("pdf") => TipoContenido::Pdf,
+        _ if payload["capitulo"].as_i64() == Some(0) => TipoContenido::Canto,
+        _ => TipoContenido::Biblia,
+    }
+}
+
+fn cumple(condicion: &Condicion, payload: &serde_json::Value) -> bool {
+    if payload["tipo"].as_str() == Some("limpiar") { return false; }
+    if condicion.tipo.is_some_and(|t| t != tipo_de(payload)) { return false; }
+    if condicion.canto_id.is_some_and(|id| payload["canto_id"].as_i64() != Some(id as i64)) { return false; }
+    if let Some(texto) = condicion.contiene.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        let texto = texto.to_lowercase();
+        let en = |campo: &str| payload[campo].as_str().is_some_and(|v| v.to_lowercase().contains(&texto));
+        if !en("libro") && !en("ruta") { return false; }
+    }
+    true
+}
+
+// Una regla se dispara solo cuando empieza a cumplirse: pasar de versículo
+// dentro del mismo sermón no repite el cambio.
+fn acciones_a_disparar(reglas: Vec<ReglaObs>, anterior: Option<&serde_json::Value>, nuevo: &serde_json::Value) -> Vec<AccionObs> {
+    reglas.into_iter()
+        .filter(|r| r.activa && cumple(&r.condicion, nuevo) && !anterior.is_some_and(|a| cumple(&r.condicion, a)))
+        .flat_map(|r| r.acciones)
+        .collect()
+}
+
+// Lo que tiene que hacer el hilo de OBS por cada disparo
+pub struct TrabajoObs {
+    config: ConfigObs,
+    acciones: Vec<AccionObs>,
+}
+
+// Atiende los disparos de a uno, en el orden en que se proyectaron
+fn atender(rx: mpsc::Receiver<TrabajoObs>, avisar_error: impl Fn(String)) {
+    for trabajo in rx {
+        let resultado = ClienteObs::conectar(&trabajo.config).and_then(|mut cliente| {
+            let resultado = trabajo.acciones.iter().try_for_each(|accion| cliente.ejecutar(accion));
+            cliente.cerrar();
+            resultado
+        });
+        if let Err(e) = resultado {
+            avisar_error(e);
+        }
+    }
+}
+
+// Arranca el hilo de OBS. Se llama una vez al iniciar la aplicación.
+pub fn iniciar(app: tauri::AppHandle) {
+    let (tx, rx) = mpsc::channel();
+    if let Ok(mut obs) = app.state::<AppState>().obs.lock() {
+        *obs = Some(tx);
+    }
+    std::thread::spawn(move || atender(rx, |e| { let _ = app.emit("obs-error", &e); }));
+}
+
+// Se llama con lo que había y lo que sale al aire.
+// En segundo plano: la proyección no espera a OBS.
+pub fn al_proyectar(app: &tauri::AppHandle, anterior: Option<&serde_json::Value>, nuevo: &serde_json::Value) {
+    let state = app.state::<AppState>();
+    let (config, reglas) = match state.multimedia_db.lock() {
+        Ok(conn) => (leer_configuracion(&conn), listar_reglas(&conn).unwrap_or_default()),
+        Err(_) => return,
+    };
+    if !config.activo { return; }
+    let acciones = acciones_a_disparar(reglas, anterior, nuevo);
+    if acciones.is_empty() { return; }
+    if let Some(tx) = state.obs.lock().ok().and_then(|obs| obs.clone()) {
+        let _ = tx.send(TrabajoObs { config, acciones });
+    }
+}
+
+// ==========================================
+// COMANDOS DE OBS
+// ==========================================
+#[tauri::command]
+pub fn get_obs_config(state: State<AppState>) -> Result<ConfigObs, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    Ok(leer_configuracion(&conn))
+}
+
+#[tauri::command]
+pub fn set_obs_config(config: ConfigObs, state: State<AppState>) -> Result<(), String> {
+    if config.host.trim().is_empty() { return Err("Indique el host de OBS".to_string()); }
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    guardar_config(&conn, CONFIG_OBS, &serde_json::to_string(&config).map_err(|e| e.to_string())?).map_err(|e| e.to_string())
+}
+
+// Prueba la conexión (con la configuración indicada o la guardada) y devuelve la versión de OBS
+#[tauri::command]
+pub async fn test_obs_connection(config: Option<ConfigObs>, state: State<'_, AppState>) -> Result<String, String> {
+    let config = match config {
+        Some(config) => config,
+        None => leer_configuracion(&*state.multimedia_db.lock().map_err(|e| e.to_string())?),
+    };
+    let mut cliente = ClienteObs::conectar(&config)?;
+    let version = cliente.peticion("GetVersion", serde_json::json!({}));
+    cliente.cerrar();
+    Ok(version?["obsVersion"].as_str().unwrap_or("").to_string())
+}
+
+// Nombres de las escenas, para armar las reglas
+#[tauri::command]
+pub async fn get_obs_scenes(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let config = leer_configuracion(&*state.multimedia_db.lock().map_err(|e| e.to_string())?);
+    let mut cliente = ClienteObs::conectar(&config)?;
+    let lista = cliente.peticion("GetSceneList", serde_json::json!({}));
+    cliente.cerrar();
+    Ok(lista?["scenes"].as_array().map(|escenas| {
+        escenas.iter().filter_map(|e| e["sceneName"].as_str().map(str::to_string)).collect()
+    }).unwrap_or_default())
+}
+
+// Ejecuta una acción a mano (botón de prueba de la regla)
+#[tauri::command]
+pub async fn run_obs_action(accion: AccionObs, state: State<'_, AppState>) -> Result<(), String> {
+    let config = leer_configuracion(&*state.multimedia_db.lock().map_err(|e| e.to_string())?);
+    let mut cliente = ClienteObs::conectar(&config)?;
+    let resultado = cliente.ejecutar(&accion);
+    cliente.cerrar();
+    resultado
+}
+
+#[tauri::command]
+pub fn get_obs_rules(state: State<AppState>) -> Result<Vec<ReglaObs>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    listar_reglas(&conn).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn save_obs_rule(regla: ReglaObs, state: State<AppState>) -> Result<i32, String> {
+    regla.validar()?;
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let condicion = serde_json::to_string(&regla.condicion).map_err(|e| e.to_string())?;
+    let acciones = serde_json::to_string(&regla.acciones).map_err(|e| e.to_string())?;
+    let activa = if regla.activa { 1 } else { 0 };
+    match regla.id {
+        Some(id) => {
+            conn.execute("UPDATE obs_reglas SET nombre = ?, activa = ?, condicion = ?, acciones = ? WHERE id = ?", params![regla.nombre.trim(), activa, condicion, acciones, id]).map_err(|e| e.to_string())?;
+            Ok(id)
+        }
+        None => {
+            conn.execute("INSERT INTO obs_reglas (nombre, activa, condicion, acciones) VALUES (?, ?, ?, ?)", params![regla.nombre.trim(), activa, condicion, acciones]).map_err(|e| e.to_string())?;
+            Ok(conn.last_insert_rowid() as i32)
+        }
+    }
+}
+
+#[tauri::command]
+pub fn delete_obs_rule(id: i32, state: State<AppState>) -> Result<(), String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM obs_reglas WHERE id = ?", params![id]).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use tungstenite::protocol::frame::coding::CloseCode;
+    use tungstenite::protocol::CloseFrame;
+
+    type Peticiones = Arc<Mutex<Vec<(String, serde_json::Value)>>>;
+
+    const SAL: &str = "sal-de-prueba";
+    const DESAFIO: &str = "desafio-de-prueba";
+
+    // Servidor obs-websocket de mentira: Hello/Identify (con contraseña si se indica)
+    // y respuestas fijas a las peticiones. Anota cada petición recibida.
+    fn servidor(contrasena: Option<&'static str>, conexiones: usize) -> (ConfigObs, Peticiones) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let puerto = listener.local_addr().unwrap().port();
+        let peticiones: Peticiones = Arc::new(Mutex::new(Vec::new()));
+        let anotadas = peticiones.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(conexiones) {
+                let mut ws = tungstenite::accept(stream.unwrap()).unwrap();
+                let enviar = |ws: &mut WebSocket<TcpStream>, op: u64, d: serde_json::Value| {
+                    ws.send(Message::Text(json!({ "op": op, "d": d }).to_string())).unwrap();
+                };
+                let mut hola = json!({ "obsWebSocketVersion": "5.0.0", "rpcVersion": 1 });
+                if contrasena.is_some() {
+                    hola["authentication"] = json!({ "challenge": DESAFIO, "salt": SAL });
+                }
+                enviar(&mut ws, OP_HELLO, hola);
+
+                // El cliente corta sin identificarse si le falta la contraseña
+                let Ok(Message::Text(identificar)) = ws.read() else { continue };
+                let identificar: serde_json::Value = serde_json::from_str(&identificar).unwrap();
+                assert_eq!(identificar["op"], OP_IDENTIFY);
+                if let Some(contrasena) = contrasena {
+                    if identificar["d"]["authentication"].as_str() != Some(autenticacion(contrasena, SAL, DESAFIO).as_str()) {
+                        let _ = ws.close(Some(CloseFrame { code: CloseCode::Library(4009), reason: "Authentication failed.".into() }));
+                        let _ = ws.flush();
+                        continue;
+                    }
+                }
+                enviar(&mut ws, OP_IDENTIFIED, json!({ "negotiatedRpcVersion": 1 }));
+
+                while let Ok(Message::Text(texto)) = ws.read() {
+                    let mensaje: serde_json::Value = serde_json::from_str(&texto).unwrap();
+                    let d = &mensaje["d"];
+                    let tipo = d["requestType"].as_str().unwrap().to_string();
+                    anotadas.lock().unwrap().push((tipo.clone(), d["requestData"].clone()));
+                    // Una respuesta ajena antes de la buena: el cliente debe ignorarla
+                    enviar(&mut ws, OP_REQUEST_RESPONSE, json!({ "requestType": tipo, "requestId": "otra", "requestStatus": { "result": true, "code": 100 } }));
+                    let (estado, datos) = match tipo.as_str() {
+                        "GetVersion" => (json!({ "result": true, "code": 100 }), json!({ "obsVersion": "30.1.2" })),
+                        "GetSceneItemId" => (json!({ "result": true, "code": 100 }), json!({ "sceneItemId": 5 })),
+                        "GetRecordStatus" => (json!({ "result": true, "code": 100 }), json!({ "outputActive": false })),
+                        "SetCurrentProgramScene" if d["requestData"]["sceneName"] == "No existe" => {
+                            (json!({ "result": false, "code": 600, "comment": "No source was found by the name of `No existe`." }), json!(null))
+                        }
+                        _ => (json!({ "result": true, "code": 100 }), json!(null)),
+                    };
+                    enviar(&mut ws, OP_REQUEST_RESPONSE, json!({ "requestType": tipo, "requestId": d["requestId"], "requestStatus": estado, "responseData": datos }));
+                }
+            }
+        });
+        (ConfigObs { activo: true, host: "127.0.0.1".to_string(), puerto, contrasena: String::new() }, peticiones)
+    }
+
+    fn tipos(peticiones: &Peticiones) -> Vec<String> {
+        peticiones.lock().unwrap().iter().map(|(tipo, _)| tipo.clone()).collect()
+    }
+
+    #[test]
+    fn conecta_sin_contrasena_y_hace_peticiones() {
+        let (config, peticiones) = servidor(None, 1);
+        let mut cliente = ClienteObs::conectar(&config).unwrap();
+        assert_eq!(cliente.peticion("GetVersion", json!({})).unwrap()["obsVersion"], "30.1.2");
+        let error = cliente.peticion("SetCurrentProgramScene", json!({ "sceneName": "No existe" })).unwrap_err();
+        assert!(error.contains("No source was found"), "{}", error);
+        cliente.cerrar();
+        assert_eq!(tipos(&peticiones), ["GetVersion", "SetCurrentProgramScene"]);
+    }
+
+    #[test]
+    fn conecta_con_desafio_de_contrasena() {
+        let (mut config, peticiones) = servidor(Some("secreto"), 3);
+        // Sin contraseña configurada no se intenta identificar
+        assert_eq!(ClienteObs::conectar(&config).err().unwrap(), "OBS pide contraseña");
+        config.contrasena = "otra".to_string();
+        assert_eq!(ClienteObs::conectar(&config).err().unwrap(), "Contraseña de OBS incorrecta");
+        config.contrasena = "secreto".to_string();
+        let mut cliente = ClienteObs::conectar(&config).unwrap();
+        cliente.peticion("GetVersion", json!({})).unwrap();
+        cliente.cerrar();
+        assert_eq!(tipos(&peticiones), ["GetVersion"]);
+    }
+
+    #[test]
+    fn ejecuta_acciones() {
+        let (config, peticiones) = servidor(None, 1);
+        let mut cliente = ClienteObs::conectar(&config).unwrap();
+        cliente.ejecutar(&AccionObs::Escena { escena: "Púlpito".into() }).unwrap();
+        cliente.ejecutar(&AccionObs::Fuente { escena: "Púlpito".into(), fuente: "Letras".into(), visible: false }).unwrap();
+        cliente.ejecutar(&AccionObs::IniciarGrabacion).unwrap();
+        // No está grabando: detener no envía StopRecord
+        cliente.ejecutar(&AccionObs::DetenerGrabacion).unwrap();
+        cliente.cerrar();
+
+        let peticiones = peticiones.lock().unwrap().clone();
+        let tipos: Vec<&str> = peticiones.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(tipos, ["SetCurrentProgramScene", "GetSceneItemId", "SetSceneItemEnabled", "GetRecordStatus", "StartRecord", "GetRecordStatus"]);
+        assert_eq!(peticiones[0].1, json!({ "sceneName": "Púlpito" }));
+        assert_eq!(peticiones[2].1, json!({ "sceneName": "Púlpito", "sceneItemId": 5, "sceneItemEnabled": false }));
+    }
+
+    #[test]
+    fn el_hilo_respeta_el_orden_de_los_disparos() {
+        let (config, peticiones) = servidor(None, 2);
+        let (tx, rx) = mpsc::channel();
+        for escena in ["Canto", "Video"] {
+            tx.send(TrabajoObs { config: config.clone(), acciones: vec![AccionObs::Escena { escena: escena.into() }] }).unwrap();
+        }
+        drop(tx);
+        let errores = Mutex::new(Vec::new());
+        atender(rx, |e| errores.lock().unwrap().push(e));
+        assert!(errores.lock().unwrap().is_empty());
+        let escenas: Vec<serde_json::Value> = peticiones.lock().unwrap().iter().map(|(_, d)| d["sceneName"].clone()).collect();
+        assert_eq!(escenas, [json!("Canto"), json!("Video")]);
+    }
+
+    fn regla(condicion: Condicion, escena: &str) -> ReglaObs {
+        ReglaObs { id: None, nombre: escena.to_string(), activa: true, condicion, acciones: vec![AccionObs::Escena { escena: escena.to_string() }] }
+    }
+
+    fn escenas(acciones: Vec<AccionObs>) -> Vec<String> {
+        acciones.into_iter().filter_map(|a| match a { AccionObs::Escena { escena } => Some(escena), _ => None }).collect()
+    }
+
+    #[test]
+    fn las_reglas_se_disparan_al_empezar_a_cumplirse() {
+        let reglas = vec![
+            regla(Condicion { tipo: Some(TipoContenido::Biblia), ..Default::default() }, "Sermón"),
+            regla(Condicion { tipo: Some(TipoContenido::Canto), canto_id: Some(4), ..Default::default() }, "Alabanza"),
+            regla(Condicion { contiene: Some("ANUNCIOS".into()), ..Default::default() }, "Anuncios"),
+        ];
+        let juan_1 = json!({ "tipo": "versiculo", "libro": "Juan", "capitulo": 3, "versiculo": 16 });
+        let juan_2 = json!({ "tipo": "versiculo", "libro": "Juan", "capitulo": 3, "versiculo": 17 });
+        let canto = json!({ "tipo": "canto", "libro": "Sublime gracia", "capitulo": 0, "versiculo": 1, "canto_id": 4 });
+        let otro_canto = json!({ "tipo": "canto", "libro": "Otro", "capitulo": 0, "versiculo": 1, "canto_id": 9 });
+        let imagen = json!({ "tipo": "imagen", "ruta": "C:/Anuncios/cena.jpg" });
+
+        assert_eq!(escenas(acciones_a_disparar(reglas.clone(), None, &juan_1)), ["Sermón"]);
+        // Mismo sermón, otro versículo: no se repite
+        assert!(acciones_a_disparar(reglas.clone(), Some(&juan_1), &juan_2).is_empty());
+        assert_eq!(escenas(acciones_a_disparar(reglas.clone(), Some(&juan_2), &canto)), ["Alabanza"]);
+        assert!(acciones_a_disparar(reglas.clone(), Some(&canto), &otro_canto).is_empty());
+        // Sin distinguir mayúsculas y también en la ruta
+        assert_eq!(escenas(acciones_a_disparar(reglas.clone(), Some(&canto), &imagen)), ["Anuncios"]);
+        // Limpiar la pantalla no cumple ninguna condición, y lo siguiente vuelve a disparar
+        let limpiar = json!({ "tipo": "limpiar" });
+        assert!(acciones_a_disparar(reglas.clone(), Some(&juan_1), &limpiar).is_empty());
+        assert_eq!(escenas(acciones_a_disparar(reglas.clone(), Some(&limpiar), &juan_1)), ["Sermón"]);
+        // Las reglas inactivas no se disparan
+        let inactivas: Vec<ReglaObs> = reglas.into_iter().map(|r| ReglaObs { activa: false, ..r }).collect();
+        assert!(acciones_a_disparar(inactivas, None, &juan_1).is_empty());
+    }
+}
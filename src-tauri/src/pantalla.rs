@@ -0,0 +1,149 @@
+// ==========================================
+// ESTADO DE LA PANTALLA (negro, sin texto, logo, congelado)
+// ==========================================
+// Estados explícitos del proyector en lugar de mandar un versículo vacío.
+// Lo que se proyecta mientras tanto se sigue guardando en `AppState.proyeccion`
+// y aparece al volver a `normal`. `live-state` informa a todos los paneles qué
+// está viendo el público.
+use crate::protocolo::{self, EventoProyeccion};
+use crate::temas::EstiloParcial;
+use crate::{emitir_proyeccion, guardar_config, leer_config, obs, salidas, AppState};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager, State};
+
+const CONFIG_LOGO: &str = "logo_imagen_id";
+const FUNDIDO_POR_DEFECTO: u32 = 500;
+const FUNDIDO_MAXIMO: u32 = 10_000;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ModoPantalla {
+    #[default]
+    Normal,
+    Negro,     // Pantalla en negro
+    SinTexto,  // Se quita el texto y queda el fondo
+    Logo,      // Imagen de logo configurada (de la tabla `imagenes`)
+    Congelado, // Se mantiene lo que había mientras el operador navega
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct EstadoPantalla {
+    pub modo: ModoPantalla,
+    pub fundido_ms: u32,
+    pub logo: Option<String>, // Ruta de la imagen, solo en modo logo
+    #[serde(skip)]
+    congelado: Option<(serde_json::Value, Option<EstiloParcial>)>, // Lo que quedó en pantalla al congelar
+}
+
+// Para cualquier panel: el modo y lo que ve el público (y lo preparado, si está congelado)
+#[derive(Serialize)]
+pub struct EstadoEnVivo {
+    pantalla: EstadoPantalla,
+    en_pantalla: Option<serde_json::Value>,
+    preparado: Option<serde_json::Value>,
+}
+
+pub fn congelada(state: &AppState) -> bool {
+    state.pantalla.lock().is_ok_and(|p| p.modo == ModoPantalla::Congelado)
+}
+
+// Contenido que ve el público ahora mismo (None en negro o con el logo)
+pub fn visible(state: &AppState) -> Option<(serde_json::Value, Option<EstiloParcial>)> {
+    let pantalla = state.pantalla.lock().ok()?;
+    match pantalla.modo {
+        ModoPantalla::Negro | ModoPantalla::Logo => None,
+        ModoPantalla::Congelado => pantalla.congelado.clone(),
+        ModoPantalla::Normal | ModoPantalla::SinTexto => state.proyeccion.lock().ok()?.clone(),
+    }
+}
+
+pub fn estado_en_vivo(state: &AppState) -> Result<EstadoEnVivo, String> {
+    let pantalla = state.pantalla.lock().map_err(|e| e.to_string())?.clone();
+    let actual = state.proyeccion.lock().map_err(|e| e.to_string())?.clone().map(|(payload, _)| payload);
+    Ok(match pantalla.modo {
+        ModoPantalla::Congelado => EstadoEnVivo { en_pantalla: pantalla.congelado.clone().map(|(payload, _)| payload), preparado: actual, pantalla },
+        ModoPantalla::Negro | ModoPantalla::Logo => EstadoEnVivo { en_pantalla: None, preparado: actual, pantalla },
+        _ => EstadoEnVivo { en_pantalla: actual, preparado: None, pantalla },
+    })
+}
+
+pub fn emitir_en_vivo(app: &tauri::AppHandle) {
+    if let Ok(estado) = estado_en_vivo(&app.state::<AppState>()) {
+        let _ = app.emit("live-state", &estado);
+    }
+}
+
+fn ruta_logo(state: &AppState) -> Result<Option<String>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let Some(id) = leer_config(&conn, CONFIG_LOGO).and_then(|v| v.parse::<i32>().ok()) else { return Ok(None) };
+    Ok(conn.query_row("SELECT COALESCE(ruta_proyeccion, ruta) FROM imagenes WHERE id = ?", params![id], |row| row.get(0)).ok())
+}
+
+// ==========================================
+// COMANDOS DE PANTALLA
+// ==========================================
+#[tauri::command]
+pub fn set_screen_mode(app: tauri::AppHandle, modo: ModoPantalla, fundido_ms: Option<u32>, state: State<AppState>) -> Result<EstadoPantalla, String> {
+    let fundido_ms = fundido_ms.unwrap_or(FUNDIDO_POR_DEFECTO);
+    if fundido_ms > FUNDIDO_MAXIMO {
+        return Err("El fundido no puede durar más de 10 segundos".to_string());
+    }
+    let logo = if modo == ModoPantalla::Logo {
+        Some(ruta_logo(&state)?.ok_or("No hay una imagen de logo configurada")?)
+    } else {
+        None
+    };
+
+    let (estado, descongelado) = {
+        let mut pantalla = state.pantalla.lock().map_err(|e| e.to_string())?;
+        let descongelado = if pantalla.modo == ModoPantalla::Congelado && modo != ModoPantalla::Congelado { pantalla.congelado.take() } else { None };
+        if modo == ModoPantalla::Congelado && pantalla.modo != ModoPantalla::Congelado {
+            pantalla.congelado = state.proyeccion.lock().map_err(|e| e.to_string())?.clone();
+        }
+        pantalla.modo = modo;
+        pantalla.fundido_ms = fundido_ms;
+        pantalla.logo = logo;
+        (pantalla.clone(), descongelado)
+    };
+
+    // Al descongelar sale lo último que se proyectó mientras tanto
+    if let Some((congelado, _)) = descongelado {
+        let actual = state.proyeccion.lock().map_err(|e| e.to_string())?.clone();
+        if let Some((payload, item)) = actual {
+            emitir_proyeccion(&app, &state, &payload, item.as_ref())?;
+            obs::al_proyectar(&app, Some(&congelado), &payload);
+        }
+    }
+
+    let evento = EventoProyeccion::Pantalla(estado.clone());
+    for (_, ventana) in salidas::ventanas_de_salidas(&app) {
+        protocolo::emitir(&ventana, &evento);
+    }
+    emitir_en_vivo(&app);
+    Ok(estado)
+}
+
+#[tauri::command]
+pub fn get_live_state(state: State<AppState>) -> Result<EstadoEnVivo, String> {
+    estado_en_vivo(&state)
+}
+
+// Elige la imagen de la biblioteca que se usa como logo (None la quita)
+#[tauri::command]
+pub fn set_logo_image(imagen_id: Option<i32>, state: State<AppState>) -> Result<(), String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    match imagen_id {
+        Some(id) => {
+            conn.query_row("SELECT id FROM imagenes WHERE id = ?", params![id], |row| row.get::<_, i32>(0)).map_err(|_| "La imagen no existe".to_string())?;
+            guardar_config(&conn, CONFIG_LOGO, &id.to_string()).map_err(|e| e.to_string())
+        }
+        None => conn.execute("DELETE FROM configuracion WHERE clave = ?", params![CONFIG_LOGO]).map(|_| ()).map_err(|e| e.to_string()),
+    }
+}
+
+#[tauri::command]
+pub fn get_logo_image(state: State<AppState>) -> Result<Option<i32>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    Ok(leer_config(&conn, CONFIG_LOGO).and_then(|v| v.parse().ok()))
+}
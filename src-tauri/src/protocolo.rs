@@ -0,0 +1,373 @@
+// ==========================================
+// PROTOCOLO DE PROYECCIÓN (eventos tipados y versionados)
+// ==========================================
+// Contrato entre el backend y las ventanas que proyectan (proyector, salidas
+// adicionales y futuros controles remotos). Los nombres de los eventos y las
+// claves JSON son los que ya usaba el proyector. Cada evento llega como
+// `{ protocolo, evento, datos }`, así la versión viaja aunque `datos` sea null.
+use crate::audio::AudioControl;
+use crate::avisos::Aviso;
+use crate::pantalla::EstadoPantalla;
+use crate::temas::{Estilo, EstilosProyector};
+use crate::temporizadores::EstadoTemporizador;
+use crate::transiciones::Transiciones;
+use crate::VideoControl;
+use serde::{Deserialize, Serialize};
+use tauri::{EventTarget, Emitter};
+
+// Subir solo con cambios que rompan la forma del JSON
+pub const VERSION_PROTOCOLO: u32 = 1;
+
+const ASPECTOS: [&str; 3] = ["contain", "cover", "fill"];
+
+fn aspecto_por_defecto() -> String { "contain".to_string() }
+fn formato_por_defecto() -> String { "jpg".to_string() }
+fn version_canto() -> String { "CANTO".to_string() }
+
+// Lo que se proyecta (payload de `update-proyeccion`)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "tipo", rename_all = "snake_case")]
+pub enum Contenido {
+    Versiculo {
+        libro: String,
+        capitulo: i32,
+        versiculo: i32,
+        texto: String,
+        #[serde(rename = "versionName")]
+        version: String,
+    },
+    // Diapositiva de un canto: `capitulo` siempre es 0 y `versiculo` es el orden de la estrofa
+    Canto {
+        libro: String, // Título del canto
+        #[serde(default)]
+        capitulo: i32,
+        versiculo: i32,
+        texto: String,
+        #[serde(rename = "versionName", default = "version_canto")]
+        version: String,
+        #[serde(default)]
+        canto_id: Option<i32>,
+    },
+    Imagen {
+        ruta: String,
+        #[serde(default = "aspecto_por_defecto")]
+        aspecto: String,
+    },
+    Video {
+        ruta: String,
+        #[serde(default)]
+        bucle: bool,
+    },
+    Pdf {
+        ruta: String,
+        pagina: i32,
+        #[serde(default = "formato_por_defecto")]
+        formato: String,
+    },
+    Limpiar, // Pantalla sin contenido
+}
+
+impl Contenido {
+    // Lee lo que envía el panel. Los payloads de texto sin `tipo` (formato anterior)
+    // se reconocen por el capítulo: 0 es un canto, cualquier otro un versículo.
+    pub fn desde_payload(mut payload: serde_json::Value) -> Result<Contenido, String> {
+        let objeto = payload.as_object_mut().ok_or("El contenido a proyectar debe ser un objeto")?;
+        if let Some(version) = objeto.get("protocolo").and_then(|v| v.as_u64()) {
+            if version > VERSION_PROTOCOLO as u64 {
+                return Err(format!("Versión de protocolo no soportada: {} (la aplicación usa la {})", version, VERSION_PROTOCOLO));
+            }
+        }
+        let tipo = objeto.get("tipo").and_then(|t| t.as_str()).map(str::to_string);
+        if tipo.is_none() || tipo.as_deref() == Some("texto") {
+            let tipo = if objeto.get("capitulo").and_then(|c| c.as_i64()) == Some(0) { "canto" } else { "versiculo" };
+            objeto.insert("tipo".to_string(), serde_json::json!(tipo));
+        }
+        let contenido: Contenido = serde_json::from_value(payload).map_err(|e| format!("Contenido de proyección inválido: {}", e))?;
+        contenido.validar()?;
+        Ok(contenido)
+    }
+
+    pub fn validar(&self) -> Result<(), String> {
+        match self {
+            Contenido::Versiculo { libro, capitulo, versiculo, .. } => {
+                if libro.trim().is_empty() { return Err("El versículo no tiene libro".to_string()); }
+                if *capitulo < 1 || *versiculo < 1 { return Err("Capítulo y versículo deben ser mayores que 0".to_string()); }
+            }
+            Contenido::Canto { capitulo, .. } if *capitulo != 0 => return Err("Las diapositivas de canto usan el capítulo 0".to_string()),
+            Contenido::Imagen { ruta, aspecto } => {
+                if ruta.trim().is_empty() { return Err("Falta la ruta de la imagen".to_string()); }
+                if !ASPECTOS.contains(&aspecto.as_str()) { return Err(format!("Aspecto no soportado: {}", aspecto)); }
+            }
+            Contenido::Video { ruta, .. } if ruta.trim().is_empty() => return Err("Falta la ruta del video".to_string()),
+            Contenido::Pdf { ruta, pagina, .. } => {
+                if ruta.trim().is_empty() { return Err("Falta la ruta del PDF".to_string()); }
+                if *pagina < 1 { return Err("La página del PDF debe ser mayor que 0".to_string()); }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub fn es_texto(&self) -> bool {
+        matches!(self, Contenido::Versiculo { .. } | Contenido::Canto { .. })
+    }
+
+    // Para salidas sin la capa de texto: queda el fondo del tema
+    pub fn quitar_texto(&mut self) {
+        if let Contenido::Versiculo { texto, .. } | Contenido::Canto { texto, .. } = self {
+            texto.clear();
+        }
+    }
+
+    // JSON canónico, con `tipo` y `protocolo`
+    pub fn a_payload(&self) -> Result<serde_json::Value, String> {
+        let mut payload = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        if let Some(objeto) = payload.as_object_mut() {
+            objeto.insert("protocolo".to_string(), serde_json::json!(VERSION_PROTOCOLO));
+        }
+        Ok(payload)
+    }
+}
+
+// Versículos y cantos llevan estilo; lo demás no
+pub fn es_texto(payload: &serde_json::Value) -> bool {
+    payload.get("tipo").and_then(|t| t.as_str()).is_none_or(|t| matches!(t, "texto" | "versiculo" | "canto"))
+}
+
+// Contenido ya resuelto para una ventana: el estilo (solo texto) y la transición van junto a él
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Proyeccion {
+    #[serde(flatten)]
+    pub contenido: Contenido,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estilo: Option<Estilo>,
+    pub transicion: Transiciones,
+}
+
+// Todos los eventos que recibe una ventana de proyección
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "evento", content = "datos", rename_all = "snake_case")]
+pub enum EventoProyeccion {
+    Contenido(Option<Proyeccion>), // None deja la ventana vacía (salida sin la capa correspondiente)
+    Estilos(EstilosProyector),
+    EstiloEnVivo(Estilo),         // Estilo recalculado de lo que está en pantalla
+    Video(VideoControl),
+    Aviso(Option<Aviso>),
+    Pantalla(EstadoPantalla),
+    Audio(AudioControl),              // Reproductor de audio del proyector
+    Temporizador(EstadoTemporizador), // Tick de un temporizador suscrito
+    TemporizadorQuitado(String),      // Nombre del temporizador que deja de mostrarse
+}
+
+impl EventoProyeccion {
+    pub fn nombre(&self) -> &'static str {
+        match self {
+            EventoProyeccion::Contenido(_) => "update-proyeccion",
+            EventoProyeccion::Estilos(_) => "update-styles",
+            EventoProyeccion::EstiloEnVivo(_) => "update-estilo-proyeccion",
+            EventoProyeccion::Video(_) => "video-control",
+            EventoProyeccion::Aviso(_) => "update-aviso",
+            EventoProyeccion::Pantalla(_) => "update-pantalla",
+            EventoProyeccion::Audio(_) => "audio-control",
+            EventoProyeccion::Temporizador(_) => "timer-tick",
+            EventoProyeccion::TemporizadorQuitado(_) => "timer-removed",
+        }
+    }
+
+    // Lo que llega a la ventana: `{ protocolo, evento, datos }`
+    pub fn mensaje(&self) -> serde_json::Value {
+        let mut mensaje = serde_json::to_value(self).unwrap_or_default();
+        if let Some(objeto) = mensaje.as_object_mut() {
+            objeto.insert("protocolo".to_string(), serde_json::json!(VERSION_PROTOCOLO));
+        }
+        mensaje
+    }
+}
+
+// Solo a esa ventana: `emit` llega a todas y cada salida recibe su propia versión del contenido
+pub fn emitir(ventana: &tauri::WebviewWindow, evento: &EventoProyeccion) {
+    let _ = ventana.emit_to(EventTarget::webview_window(ventana.label()), evento.nombre(), evento.mensaje());
+}
+
+#[tauri::command]
+pub fn get_protocol_version() -> u32 {
+    VERSION_PROTOCOLO
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avisos::EstiloAviso;
+    use crate::pantalla::ModoPantalla;
+    use crate::temporizadores::ModoTemporizador;
+    use crate::transiciones::{TipoTransicion, Transicion};
+    use serde_json::json;
+
+    fn estilo_json() -> serde_json::Value {
+        json!({
+            "fontFamily": "sans-serif", "fontSize": null, "textColor": "#ffffff", "shadow": null, "outline": null,
+            "align": "center", "bgColor": "#000000", "bgImage": "", "bgVideo": "",
+            "margins": { "top": 0.0, "right": 0.0, "bottom": 0.0, "left": 0.0 },
+            "transitions": { "texto": { "tipo": "fundido", "duracion": 0.3 }, "fondo": { "tipo": "fundido", "duracion": 0.5 } }
+        })
+    }
+
+    fn corte() -> Transiciones {
+        Transiciones::iguales(Transicion::nueva(TipoTransicion::Corte, 0.0))
+    }
+
+    // --- Contenido ---
+    #[test]
+    fn contenido_versiculo() {
+        let c = Contenido::Versiculo { libro: "Juan".into(), capitulo: 3, versiculo: 16, texto: "Porque de tal manera...".into(), version: "RVR1960".into() };
+        assert_eq!(c.a_payload().unwrap(), json!({
+            "tipo": "versiculo", "libro": "Juan", "capitulo": 3, "versiculo": 16, "texto": "Porque de tal manera...", "versionName": "RVR1960", "protocolo": 1
+        }));
+    }
+
+    #[test]
+    fn contenido_canto() {
+        let c = Contenido::Canto { libro: "Cuán grande es Él".into(), capitulo: 0, versiculo: 2, texto: "Señor mi Dios".into(), version: "CANTO".into(), canto_id: Some(7) };
+        assert_eq!(c.a_payload().unwrap(), json!({
+            "tipo": "canto", "libro": "Cuán grande es Él", "capitulo": 0, "versiculo": 2, "texto": "Señor mi Dios", "versionName": "CANTO", "canto_id": 7, "protocolo": 1
+        }));
+    }
+
+    #[test]
+    fn contenido_multimedia_y_limpiar() {
+        assert_eq!(Contenido::Imagen { ruta: "C:/img/a.jpg".into(), aspecto: "cover".into() }.a_payload().unwrap(),
+            json!({ "tipo": "imagen", "ruta": "C:/img/a.jpg", "aspecto": "cover", "protocolo": 1 }));
+        assert_eq!(Contenido::Video { ruta: "C:/v/b.mp4".into(), bucle: true }.a_payload().unwrap(),
+            json!({ "tipo": "video", "ruta": "C:/v/b.mp4", "bucle": true, "protocolo": 1 }));
+        assert_eq!(Contenido::Pdf { ruta: "C:/pdf/c".into(), pagina: 4, formato: "png".into() }.a_payload().unwrap(),
+            json!({ "tipo": "pdf", "ruta": "C:/pdf/c", "pagina": 4, "formato": "png", "protocolo": 1 }));
+        assert_eq!(Contenido::Limpiar.a_payload().unwrap(), json!({ "tipo": "limpiar", "protocolo": 1 }));
+    }
+
+    // --- Payloads del formato anterior (sin `tipo`) ---
+    #[test]
+    fn legado_sin_tipo_se_reconoce_por_capitulo() {
+        let versiculo = Contenido::desde_payload(json!({ "libro": "Salmos", "capitulo": 23, "versiculo": 1, "texto": "Jehová es mi pastor", "versionName": "RVR1960" })).unwrap();
+        assert_eq!(versiculo, Contenido::Versiculo { libro: "Salmos".into(), capitulo: 23, versiculo: 1, texto: "Jehová es mi pastor".into(), version: "RVR1960".into() });
+
+        let canto = Contenido::desde_payload(json!({ "libro": "Sublime gracia", "capitulo": 0, "versiculo": 1, "texto": "Sublime gracia del Señor" })).unwrap();
+        assert_eq!(canto.a_payload().unwrap(), json!({
+            "tipo": "canto", "libro": "Sublime gracia", "capitulo": 0, "versiculo": 1, "texto": "Sublime gracia del Señor", "versionName": "CANTO", "canto_id": null, "protocolo": 1
+        }));
+
+        // `tipo: "texto"` también era válido antes
+        let texto = Contenido::desde_payload(json!({ "tipo": "texto", "libro": "Juan", "capitulo": 1, "versiculo": 1, "texto": "En el principio", "versionName": "NVI" })).unwrap();
+        assert!(matches!(texto, Contenido::Versiculo { .. }));
+
+        // Valores por defecto de imagen y PDF
+        assert_eq!(Contenido::desde_payload(json!({ "tipo": "imagen", "ruta": "a.jpg" })).unwrap(), Contenido::Imagen { ruta: "a.jpg".into(), aspecto: "contain".into() });
+        assert_eq!(Contenido::desde_payload(json!({ "tipo": "pdf", "ruta": "d", "pagina": 1 })).unwrap(), Contenido::Pdf { ruta: "d".into(), pagina: 1, formato: "jpg".into() });
+    }
+
+    #[test]
+    fn rechaza_versiones_futuras_y_payloads_invalidos() {
+        assert!(Contenido::desde_payload(json!({ "tipo": "limpiar", "protocolo": 2 })).is_err());
+        assert!(Contenido::desde_payload(json!({ "tipo": "limpiar", "protocolo": 1 })).is_ok());
+        assert!(Contenido::desde_payload(json!("Juan 3:16")).is_err());
+        assert!(Contenido::desde_payload(json!({ "tipo": "imagen", "ruta": "a.jpg", "aspecto": "stretch" })).is_err());
+        assert!(Contenido::desde_payload(json!({ "tipo": "pdf", "ruta": "d", "pagina": 0 })).is_err());
+        assert!(Contenido::desde_payload(json!({ "libro": "Juan", "capitulo": 3, "versiculo": 0, "texto": "", "versionName": "NVI" })).is_err());
+    }
+
+    // --- Eventos ---
+    #[test]
+    fn evento_contenido() {
+        let proyeccion = Proyeccion { contenido: Contenido::Limpiar, estilo: None, transicion: corte() };
+        let evento = EventoProyeccion::Contenido(Some(proyeccion));
+        assert_eq!(evento.nombre(), "update-proyeccion");
+        assert_eq!(evento.mensaje(), json!({
+            "protocolo": 1, "evento": "contenido",
+            "datos": { "tipo": "limpiar", "transicion": { "texto": { "tipo": "corte", "duracion": 0.0 }, "fondo": { "tipo": "corte", "duracion": 0.0 } } }
+        }));
+
+        let con_estilo = Proyeccion {
+            contenido: Contenido::Versiculo { libro: "Juan".into(), capitulo: 3, versiculo: 16, texto: "...".into(), version: "NVI".into() },
+            estilo: Some(Estilo::default()),
+            transicion: corte(),
+        };
+        let datos = EventoProyeccion::Contenido(Some(con_estilo)).mensaje()["datos"].take();
+        assert_eq!(datos["tipo"], "versiculo");
+        assert_eq!(datos["estilo"], estilo_json());
+
+        // Salida sin la capa: la versión viaja igual aunque no haya datos
+        assert_eq!(EventoProyeccion::Contenido(None).mensaje(), json!({ "protocolo": 1, "evento": "contenido", "datos": null }));
+    }
+
+    #[test]
+    fn evento_estilos() {
+        let evento = EventoProyeccion::Estilos(EstilosProyector::default());
+        assert_eq!(evento.nombre(), "update-styles");
+        assert_eq!(evento.mensaje(), json!({ "protocolo": 1, "evento": "estilos", "datos": { "biblia": estilo_json(), "cantos": estilo_json() } }));
+
+        let evento = EventoProyeccion::EstiloEnVivo(Estilo::default());
+        assert_eq!(evento.nombre(), "update-estilo-proyeccion");
+        assert_eq!(evento.mensaje(), json!({ "protocolo": 1, "evento": "estilo_en_vivo", "datos": estilo_json() }));
+    }
+
+    #[test]
+    fn evento_video() {
+        let evento = EventoProyeccion::Video(VideoControl::Seek { segundos: 30.0 });
+        assert_eq!(evento.nombre(), "video-control");
+        assert_eq!(evento.mensaje(), json!({ "protocolo": 1, "evento": "video", "datos": { "accion": "seek", "segundos": 30.0 } }));
+        assert_eq!(EventoProyeccion::Video(VideoControl::Play).mensaje()["datos"], json!({ "accion": "play" }));
+    }
+
+    #[test]
+    fn evento_aviso() {
+        let aviso = Aviso { id: 3, texto: "Padres del niño #12".into(), duracion: 15.0, estilo: EstiloAviso::default() };
+        let evento = EventoProyeccion::Aviso(Some(aviso));
+        assert_eq!(evento.nombre(), "update-aviso");
+        assert_eq!(evento.mensaje(), json!({
+            "protocolo": 1, "evento": "aviso",
+            "datos": { "id": 3, "texto": "Padres del niño #12", "duracion": 15.0, "estilo": { "color": "#ffffff", "fondo": "#b91c1c", "posicion": "abajo", "modo": "fijo", "velocidad": 120.0 } }
+        }));
+        assert_eq!(EventoProyeccion::Aviso(None).mensaje(), json!({ "protocolo": 1, "evento": "aviso", "datos": null }));
+    }
+
+    #[test]
+    fn evento_pantalla() {
+        let mut estado = EstadoPantalla::default();
+        estado.modo = ModoPantalla::SinTexto;
+        estado.fundido_ms = 500;
+        let evento = EventoProyeccion::Pantalla(estado);
+        assert_eq!(evento.nombre(), "update-pantalla");
+        assert_eq!(evento.mensaje(), json!({ "protocolo": 1, "evento": "pantalla", "datos": { "modo": "sin_texto", "fundido_ms": 500, "logo": null } }));
+    }
+
+    #[test]
+    fn evento_audio() {
+        let evento = EventoProyeccion::Audio(AudioControl::Volume { nivel: 0.5 });
+        assert_eq!(evento.nombre(), "audio-control");
+        assert_eq!(evento.mensaje(), json!({ "protocolo": 1, "evento": "audio", "datos": { "accion": "volume", "nivel": 0.5 } }));
+        assert_eq!(EventoProyeccion::Audio(AudioControl::FadeOut { segundos: 3.0 }).mensaje()["datos"], json!({ "accion": "fade_out", "segundos": 3.0 }));
+        assert_eq!(EventoProyeccion::Audio(AudioControl::Play).mensaje()["datos"], json!({ "accion": "play" }));
+    }
+
+    #[test]
+    fn evento_temporizador() {
+        let estado = EstadoTemporizador {
+            nombre: "Inicio".into(),
+            modo: ModoTemporizador::Regresiva { segundos: 300 },
+            texto: "04:59".into(),
+            segundos: 299,
+            corriendo: true,
+            terminado: false,
+            ventanas: vec!["projector".into()],
+        };
+        let evento = EventoProyeccion::Temporizador(estado);
+        assert_eq!(evento.nombre(), "timer-tick");
+        assert_eq!(evento.mensaje(), json!({
+            "protocolo": 1, "evento": "temporizador",
+            "datos": { "nombre": "Inicio", "modo": "regresiva", "segundos": 299, "texto": "04:59", "corriendo": true, "terminado": false, "ventanas": ["projector"] }
+        }));
+
+        let evento = EventoProyeccion::TemporizadorQuitado("Inicio".into());
+        assert_eq!(evento.nombre(), "timer-removed");
+        assert_eq!(evento.mensaje(), json!({ "protocolo": 1, "evento": "temporizador_quitado", "datos": "Inicio" }));
+    }
+}
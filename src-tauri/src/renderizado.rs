@@ -0,0 +1,407 @@
+// ==========================================
+// RENDERIZADO DE DIAPOSITIVAS SIN WEBVIEW (miniaturas y exportación)
+// ==========================================
+// Compone fondo + texto con el estilo del tema en un `RgbaImage`, imitando
+// lo que dibuja el proyector. Todo se hace en CPU.
+use crate::maquetado::{self, INTERLINEADO};
+use crate::temas::{self, Alineacion, Estilo, EstiloParcial};
+use crate::{ffmpeg, imagen, leer_config, parse_color_hex, resolucion_proyector, AppState};
+use ab_glyph::{point, Font, FontArc, ScaleFont};
+use image::{DynamicImage, GrayImage, Luma, Rgba, RgbaImage};
+use rusqlite::params;
+use serde::Deserialize;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use tauri::{Manager, State};
+
+const TAMANO_REFERENCIA: f32 = 48.0; // text-5xl
+const MAXIMO_MINIATURAS: usize = 500;
+const MAXIMO_FOTOGRAMAS: usize = 100;
+
+// Una diapositiva ya resuelta, lista para dibujar
+pub struct DiapositivaRender<'a> {
+    pub texto: &'a str,
+    pub referencia: Option<String>, // "Juan 3:16" en los versículos
+    pub estilo: &'a Estilo,
+    pub fondo_video: Option<&'a Path>, // Fotograma del video de fondo, si se pudo extraer
+}
+
+pub fn renderizar(diapositiva: &DiapositivaRender, resolucion: (u32, u32)) -> RgbaImage {
+    let (ancho, alto) = resolucion;
+    let estilo = diapositiva.estilo;
+    let mut lienzo = RgbaImage::from_pixel(ancho, alto, Rgba([0, 0, 0, 255]));
+
+    // Área segura (márgenes): fuera de ella la pantalla queda en negro, igual que en el proyector
+    let m = estilo.margenes;
+    let x0 = (ancho as f32 * m.left / 100.0).round() as u32;
+    let y0 = (alto as f32 * m.top / 100.0).round() as u32;
+    let area_ancho = ((ancho as f32 * (1.0 - (m.left + m.right) / 100.0)).round() as u32).max(1);
+    let area_alto = ((alto as f32 * (1.0 - (m.top + m.bottom) / 100.0)).round() as u32).max(1);
+
+    let (r, g, b) = parse_color_hex(&estilo.fondo_color).unwrap_or((0, 0, 0));
+    let mut area = RgbaImage::from_pixel(area_ancho, area_alto, Rgba([r, g, b, 255]));
+    let fondo = if !estilo.fondo_imagen.is_empty() { Some(Path::new(&estilo.fondo_imagen)) } else { diapositiva.fondo_video };
+    if let Some(fondo) = fondo.and_then(|ruta| image::open(ruta).ok()) {
+        area = fondo.resize_to_fill(area_ancho, area_alto, image::imageops::FilterType::Triangle).into_rgba8();
+    }
+    if diapositiva.fondo_video.is_some() && estilo.fondo_imagen.is_empty() {
+        // Capa oscura (bg-black/30) que el proyector pone sobre los videos de fondo
+        for pixel in area.pixels_mut() {
+            for canal in pixel.0.iter_mut().take(3) { *canal = (*canal as f32 * 0.7) as u8; }
+        }
+    }
+
+    let font = maquetado::cargar_fuente(&estilo.fuente);
+    let mut mascara = GrayImage::new(area_ancho, area_alto);
+
+    // Texto principal, centrado verticalmente en su caja
+    let caja = maquetado::caja_texto(estilo, resolucion, diapositiva.referencia.is_some());
+    let resultado = maquetado::ajustar(&font, diapositiva.texto, caja, estilo.tamano);
+    let relleno = (area_ancho as f32 - caja.0) / 2.0;
+    let mut y = relleno + ((caja.1 - resultado.alto) / 2.0).max(0.0);
+    for linea in &resultado.lineas {
+        let ancho_linea = maquetado::ancho_texto(&font, resultado.tamano, linea);
+        let x = match estilo.alineacion {
+            Alineacion::Left => relleno,
+            Alineacion::Center => relleno + (caja.0 - ancho_linea) / 2.0,
+            Alineacion::Right => relleno + caja.0 - ancho_linea,
+        };
+        dibujar_linea(&mut mascara, &font, resultado.tamano, linea, x, y);
+        y += resultado.tamano * INTERLINEADO;
+    }
+
+    // Referencia bíblica abajo a la derecha
+    if let Some(referencia) = &diapositiva.referencia {
+        let ancho_ref = maquetado::ancho_texto(&font, TAMANO_REFERENCIA, referencia);
+        let x = area_ancho as f32 - relleno - ancho_ref - 24.0; // pr-4 + borde
+        let y = area_alto as f32 - relleno - TAMANO_REFERENCIA;
+        dibujar_linea(&mut mascara, &font, TAMANO_REFERENCIA, referencia, x, y);
+    }
+
+    let color_texto = parse_color_hex(&estilo.color).unwrap_or((255, 255, 255));
+    if let Some(sombra) = &estilo.sombra {
+        let mut desplazada = GrayImage::new(area_ancho, area_alto);
+        image::imageops::overlay(&mut desplazada, &mascara, sombra.x.round() as i64, sombra.y.round() as i64);
+        let difusa = if sombra.desenfoque > 0.0 { image::imageops::fast_blur(&desplazada, sombra.desenfoque / 2.0) } else { desplazada };
+        componer(&mut area, &difusa, parse_color_hex(&sombra.color).unwrap_or((0, 0, 0)));
+    }
+    if let Some(contorno) = estilo.contorno.as_ref().filter(|c| c.ancho > 0.0) {
+        componer(&mut area, &dilatar(&mascara, contorno.ancho), parse_color_hex(&contorno.color).unwrap_or((0, 0, 0)));
+    }
+    componer(&mut area, &mascara, color_texto);
+
+    image::imageops::overlay(&mut lienzo, &area, x0 as i64, y0 as i64);
+    lienzo
+}
+
+// Dibuja una línea en la máscara de cobertura. `y` es la parte superior de la línea (caja de 1.25).
+fn dibujar_linea(mascara: &mut GrayImage, font: &FontArc, tamano: f32, texto: &str, x: f32, y: f32) {
+    let escala = maquetado::escala(font, tamano);
+    let escalada = font.as_scaled(escala);
+    // Como en CSS, el espacio extra del interlineado se reparte arriba y abajo
+    let base = y + (tamano * INTERLINEADO - (escalada.ascent() - escalada.descent())) / 2.0 + escalada.ascent();
+    let mut cursor = x;
+    let mut anterior = None;
+    for c in texto.chars() {
+        let id = escalada.glyph_id(c);
+        if let Some(prev) = anterior { cursor += escalada.kern(prev, id); }
+        anterior = Some(id);
+        if let Some(contorno) = font.outline_glyph(id.with_scale_and_position(escala, point(cursor, base))) {
+            let limites = contorno.px_bounds();
+            contorno.draw(|gx, gy, cobertura| {
+                let px = limites.min.x as i32 + gx as i32;
+                let py = limites.min.y as i32 + gy as i32;
+                if px >= 0 && py >= 0 && (px as u32) < mascara.width() && (py as u32) < mascara.height() {
+                    let actual = mascara.get_pixel_mut(px as u32, py as u32);
+                    actual.0[0] = actual.0[0].max((cobertura.clamp(0.0, 1.0) * 255.0) as u8);
+                }
+            });
+        }
+        cursor += escalada.h_advance(id);
+    }
+}
+
+// Engorda la máscara `radio` píxeles para el contorno
+fn dilatar(mascara: &GrayImage, radio: f32) -> GrayImage {
+    let r = radio.ceil() as i32;
+    let mut resultado = mascara.clone();
+    for dy in -r..=r {
+        for dx in -r..=r {
+            if (dx * dx + dy * dy) as f32 > radio * radio || (dx == 0 && dy == 0) { continue; }
+            for (x, y, pixel) in mascara.enumerate_pixels().filter(|(_, _, p)| p.0[0] > 0) {
+                let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+                if nx >= 0 && ny >= 0 && (nx as u32) < mascara.width() && (ny as u32) < mascara.height() {
+                    let destino = resultado.get_pixel_mut(nx as u32, ny as u32);
+                    *destino = Luma([destino.0[0].max(pixel.0[0])]);
+                }
+            }
+        }
+    }
+    resultado
+}
+
+fn componer(destino: &mut RgbaImage, mascara: &GrayImage, (r, g, b): (u8, u8, u8)) {
+    for (pixel, cobertura) in destino.pixels_mut().zip(mascara.pixels()) {
+        let a = cobertura.0[0] as f32 / 255.0;
+        if a == 0.0 { continue; }
+        let mezclar = |fondo: u8, color: u8| (fondo as f32 * (1.0 - a) + color as f32 * a).round() as u8;
+        *pixel = Rgba([mezclar(pixel.0[0], r), mezclar(pixel.0[1], g), mezclar(pixel.0[2], b), 255]);
+    }
+}
+
+// ==========================================
+// COMANDOS DE RENDERIZADO
+// ==========================================
+// Un elemento a dibujar: el mismo payload que recibe `trigger_projection`
+#[derive(Deserialize, Clone, Debug)]
+pub struct ElementoRender {
+    verse: serde_json::Value,
+    #[serde(default)]
+    estilo: Option<EstiloParcial>,
+}
+
+// Nombre de archivo de caché a partir de lo que determina su contenido
+fn clave_cache(partes: &[&str]) -> String {
+    let mut hasher = DefaultHasher::new();
+    partes.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+// Deja en la carpeta de caché solo los `maximo` archivos más recientes
+fn podar_cache(carpeta: &Path, maximo: usize) {
+    let Ok(entradas) = std::fs::read_dir(carpeta) else { return };
+    let mut archivos: Vec<(std::time::SystemTime, PathBuf)> = entradas.filter_map(Result::ok)
+        .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+        .collect();
+    if archivos.len() <= maximo { return; }
+    archivos.sort_by_key(|(modificado, _)| std::cmp::Reverse(*modificado));
+    for (_, ruta) in archivos.into_iter().skip(maximo) {
+        let _ = std::fs::remove_file(ruta);
+    }
+}
+
+// Fotograma estático del video de fondo (requiere ffmpeg). Se identifica por la ruta completa
+// y la fecha de modificación: dos videos con el mismo nombre o un video reemplazado no se confunden.
+fn fotograma_fondo(app: &tauri::AppHandle, state: &AppState, video: &str) -> Option<PathBuf> {
+    let ffmpeg_bin = {
+        let conn = state.multimedia_db.lock().ok()?;
+        ffmpeg::buscar_binario("ffmpeg", leer_config(&conn, ffmpeg::CONFIG_FFMPEG))?
+    };
+    let modificado = std::fs::metadata(video).and_then(|m| m.modified()).ok()?
+        .duration_since(std::time::UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default();
+    let carpeta = app.path().app_data_dir().ok()?.join("fondos");
+    std::fs::create_dir_all(&carpeta).ok()?;
+    let salida = carpeta.join(format!("{}.jpg", clave_cache(&[video, &modificado.to_string()])));
+    if !salida.exists() {
+        ffmpeg::generar_poster(&ffmpeg_bin, video, 1.0, &salida).ok()?;
+        podar_cache(&carpeta, MAXIMO_FOTOGRAMAS);
+    }
+    Some(salida)
+}
+
+// Texto, referencia y estilo resueltos de un elemento, antes de dibujarlo
+struct ElementoPreparado {
+    texto: String,
+    referencia: Option<String>,
+    estilo: Estilo,
+    fondo_video: Option<PathBuf>,
+}
+
+impl ElementoPreparado {
+    fn renderizar(&self, resolucion: (u32, u32)) -> RgbaImage {
+        let diapositiva = DiapositivaRender { texto: &self.texto, referencia: self.referencia.clone(), estilo: &self.estilo, fondo_video: self.fondo_video.as_deref() };
+        renderizar(&diapositiva, resolucion)
+    }
+}
+
+fn preparar_elemento(app: &tauri::AppHandle, state: &AppState, elemento: &ElementoRender) -> Result<ElementoPreparado, String> {
+    let estilo = temas::resolver_estilo(state, &elemento.verse, elemento.estilo.as_ref())?
+        .ok_or("Solo se pueden renderizar diapositivas de texto")?;
+    let v = &elemento.verse;
+    let es_canto = v["capitulo"].as_i64() == Some(0);
+    let texto = v["texto"].as_str().unwrap_or_default();
+    // Igual que el proyector: los versículos van entre comillas y con su referencia
+    let (texto, referencia) = if es_canto {
+        (texto.to_string(), None)
+    } else {
+        let versiculo = v["versiculo"].as_i64().map(|n| n.to_string()).or_else(|| v["versiculo"].as_str().map(str::to_string)).unwrap_or_default();
+        (format!("\"{}\"", texto), Some(format!("{} {}:{}", v["libro"].as_str().unwrap_or_default(), v["capitulo"], versiculo).to_uppercase()))
+    };
+    let fondo_video = (!estilo.fondo_video.is_empty()).then(|| fotograma_fondo(app, state, &estilo.fondo_video)).flatten();
+    Ok(ElementoPreparado { texto, referencia, estilo, fondo_video })
+}
+
+fn renderizar_elemento(app: &tauri::AppHandle, state: &AppState, elemento: &ElementoRender, resolucion: (u32, u32)) -> Result<RgbaImage, String> {
+    Ok(preparar_elemento(app, state, elemento)?.renderizar(resolucion))
+}
+
+// None = la resolución del proyector
+fn resolucion_pedida(ancho: Option<u32>, alto: Option<u32>) -> Result<Option<(u32, u32)>, String> {
+    match (ancho, alto) {
+        (Some(ancho), Some(alto)) if (16..=7680).contains(&ancho) && (16..=4320).contains(&alto) => Ok(Some((ancho, alto))),
+        (Some(_), Some(_)) => Err("La resolución debe estar entre 16x16 y 7680x4320".to_string()),
+        _ => Ok(None),
+    }
+}
+
+fn formato_exportacion(formato: Option<String>) -> Result<String, String> {
+    let formato = formato.unwrap_or_else(|| "png".to_string());
+    if formato != "png" && formato != "jpg" {
+        return Err(format!("Formato de exportación no soportado: {}", formato));
+    }
+    Ok(formato)
+}
+
+// Miniatura PNG de una diapositiva (480px de ancho por defecto). Devuelve la ruta del archivo generado.
+// Se dibuja a la resolución del proyector y luego se reduce, para que se vea igual que en pantalla.
+// El archivo se nombra por su contenido: la misma diapositiva reutiliza su miniatura y un cambio
+// de texto o de tema genera otra. La carpeta se poda para no crecer sin límite.
+#[tauri::command]
+pub async fn render_slide_preview(app: tauri::AppHandle, elemento: ElementoRender, ancho: Option<u32>, state: State<'_, AppState>) -> Result<String, String> {
+    let resolucion = resolucion_proyector(&app);
+    let ancho = ancho.unwrap_or(480).clamp(16, resolucion.0);
+    let alto = ((ancho as f32 * resolucion.1 as f32 / resolucion.0 as f32).round() as u32).max(1);
+    let preparado = preparar_elemento(&app, &state, &elemento)?;
+
+    let carpeta = app.path().app_data_dir().map_err(|e| e.to_string())?.join("miniaturas");
+    std::fs::create_dir_all(&carpeta).map_err(|e| e.to_string())?;
+    let estilo = serde_json::to_string(&preparado.estilo).map_err(|e| e.to_string())?;
+    let fondo = preparado.fondo_video.as_ref().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+    let medidas = format!("{}x{}@{}x{}", resolucion.0, resolucion.1, ancho, alto);
+    let clave = clave_cache(&[&preparado.texto, preparado.referencia.as_deref().unwrap_or_default(), &estilo, &fondo, &medidas]);
+    let salida = carpeta.join(format!("{}.png", clave));
+    if salida.exists() {
+        return Ok(salida.to_string_lossy().to_string());
+    }
+
+    let completa = preparado.renderizar(resolucion);
+    let imagen_render = image::imageops::resize(&completa, ancho, alto, image::imageops::FilterType::Triangle);
+    imagen::guardar(DynamicImage::ImageRgba8(imagen_render), &salida)?;
+    podar_cache(&carpeta, MAXIMO_MINIATURAS);
+    Ok(salida.to_string_lossy().to_string())
+}
+
+// Exporta una secuencia numerada (001.png, 002.png...) en `carpeta`. Formato "png" o "jpg".
+#[tauri::command]
+pub async fn export_slides(app: tauri::AppHandle, elementos: Vec<ElementoRender>, carpeta: String, formato: Option<String>, ancho: Option<u32>, alto: Option<u32>, state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    exportar(&app, &state, &elementos, &carpeta, formato, ancho, alto)
+}
+
+// Todas las diapositivas de un canto con su tema
+#[tauri::command]
+pub async fn export_song_images(app: tauri::AppHandle, canto_id: i32, carpeta: String, formato: Option<String>, ancho: Option<u32>, alto: Option<u32>, state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let elementos: Vec<ElementoRender> = {
+        let conn = state.cantos_db.lock().map_err(|e| e.to_string())?;
+        let titulo: String = conn.query_row("SELECT titulo FROM cantos WHERE id = ?", params![canto_id], |row| row.get(0)).map_err(|e| e.to_string())?;
+        let mut stmt = conn.prepare("SELECT orden, texto FROM diapositivas WHERE canto_id = ? ORDER BY orden").map_err(|e| e.to_string())?;
+        let iter = stmt.query_map(params![canto_id], |row| Ok((row.get::<_, i32>(0)?, row.get::<_, String>(1)?))).map_err(|e| e.to_string())?;
+        iter.filter_map(Result::ok).map(|(orden, texto)| ElementoRender {
+            verse: serde_json::json!({ "libro": titulo, "capitulo": 0, "versiculo": orden, "texto": texto, "versionName": "CANTO", "canto_id": canto_id }),
+            estilo: None,
+        }).collect()
+    };
+    exportar(&app, &state, &elementos, &carpeta, formato, ancho, alto)
+}
+
+fn exportar(app: &tauri::AppHandle, state: &AppState, elementos: &[ElementoRender], carpeta: &str, formato: Option<String>, ancho: Option<u32>, alto: Option<u32>) -> Result<Vec<String>, String> {
+    let formato = formato_exportacion(formato)?;
+    let resolucion = resolucion_pedida(ancho, alto)?.unwrap_or_else(|| resolucion_proyector(app));
+    let carpeta = PathBuf::from(carpeta);
+    std::fs::create_dir_all(&carpeta).map_err(|e| e.to_string())?;
+
+    let mut archivos = Vec::new();
+    for (i, elemento) in elementos.iter().enumerate() {
+        let imagen_render = renderizar_elemento(app, state, elemento, resolucion)?;
+        let salida = carpeta.join(format!("{:03}.{}", i + 1, formato));
+        imagen::guardar(DynamicImage::ImageRgba8(imagen_render), &salida)?;
+        archivos.push(salida.to_string_lossy().to_string());
+    }
+    Ok(archivos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temas::{Contorno, Margenes};
+
+    const RESOLUCION: (u32, u32) = (400, 200);
+
+    fn dibujar(texto: &str, estilo: &Estilo) -> RgbaImage {
+        renderizar(&DiapositivaRender { texto, referencia: None, estilo, fondo_video: None }, RESOLUCION)
+    }
+
+    fn pintados(imagen: &RgbaImage) -> usize {
+        imagen.pixels().filter(|p| p.0[..3] != [0, 0, 0]).count()
+    }
+
+    #[test]
+    fn fuera_de_los_margenes_queda_negro() {
+        let estilo = Estilo { fondo_color: "#204080".to_string(), margenes: Margenes { top: 10.0, right: 10.0, bottom: 10.0, left: 10.0 }, ..Default::default() };
+        let imagen = dibujar("Amén", &estilo);
+        // Margen izquierdo (40 px), superior (20 px), derecho e inferior
+        for (x, y) in [(0, 0), (39, 100), (200, 19), (360, 100), (200, 180), (399, 199)] {
+            assert_eq!(imagen.get_pixel(x, y).0, [0, 0, 0, 255], "({}, {})", x, y);
+        }
+        // Dentro del área segura, lejos del texto, se ve el fondo del tema
+        assert_eq!(imagen.get_pixel(45, 25).0, [0x20, 0x40, 0x80, 255]);
+    }
+
+    #[test]
+    fn el_texto_se_dibuja_dentro_de_su_caja_con_el_color_del_tema() {
+        let estilo = Estilo { color: "#ff0000".to_string(), ..Default::default() };
+        let imagen = dibujar("Gracia y paz", &estilo);
+        let (ancho, alto) = maquetado::caja_texto(&estilo, RESOLUCION, false);
+        let relleno = (RESOLUCION.0 as f32 - ancho) / 2.0;
+
+        let rojos: Vec<(u32, u32)> = imagen.enumerate_pixels().filter(|(_, _, p)| p.0 == [255, 0, 0, 255]).map(|(x, y, _)| (x, y)).collect();
+        assert!(!rojos.is_empty());
+        for (x, y, pixel) in imagen.enumerate_pixels().filter(|(_, _, p)| p.0[..3] != [0, 0, 0]) {
+            // Solo tinta roja (con antialiasing): ni verde ni azul
+            assert_eq!(&pixel.0[1..3], &[0, 0]);
+            assert!((x as f32) >= relleno - 1.0 && (x as f32) <= relleno + ancho + 1.0, "x = {}", x);
+            assert!((y as f32) >= relleno - 1.0 && (y as f32) <= relleno + alto + 1.0, "y = {}", y);
+        }
+    }
+
+    #[test]
+    fn el_contorno_agranda_lo_dibujado() {
+        let sin_contorno = Estilo::default();
+        let con_contorno = Estilo { contorno: Some(Contorno { color: "#00ff00".to_string(), ancho: 3.0 }), ..Default::default() };
+        let liso = dibujar("Aleluya", &sin_contorno);
+        let contorneado = dibujar("Aleluya", &con_contorno);
+        assert!(pintados(&contorneado) > pintados(&liso));
+        assert!(contorneado.pixels().any(|p| p.0 == [0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn dilatar_engorda_la_mascara_en_un_circulo() {
+        let mut mascara = GrayImage::new(11, 11);
+        mascara.put_pixel(5, 5, Luma([255]));
+        let dilatada = dilatar(&mascara, 2.0);
+        for (x, y) in [(5, 5), (7, 5), (3, 5), (5, 7), (5, 3), (6, 6)] {
+            assert_eq!(dilatada.get_pixel(x, y).0[0], 255, "({}, {})", x, y);
+        }
+        // Fuera del radio: (8, 5) está a 3 px y (7, 7) a 2.83 px
+        for (x, y) in [(8, 5), (7, 7), (0, 0)] {
+            assert_eq!(dilatada.get_pixel(x, y).0[0], 0, "({}, {})", x, y);
+        }
+        // La original no se toca
+        assert_eq!(mascara.pixels().filter(|p| p.0[0] > 0).count(), 1);
+    }
+
+    #[test]
+    fn la_exportacion_valida_formato_y_resolucion() {
+        assert_eq!(formato_exportacion(None).unwrap(), "png");
+        assert_eq!(formato_exportacion(Some("jpg".to_string())).unwrap(), "jpg");
+        assert!(formato_exportacion(Some("gif".to_string())).is_err());
+        assert!(formato_exportacion(Some("PNG".to_string())).is_err());
+
+        assert_eq!(resolucion_pedida(Some(1920), Some(1080)).unwrap(), Some((1920, 1080)));
+        assert_eq!(resolucion_pedida(None, None).unwrap(), None);
+        assert_eq!(resolucion_pedida(Some(1920), None).unwrap(), None);
+        assert!(resolucion_pedida(Some(15), Some(100)).is_err());
+        assert!(resolucion_pedida(Some(7681), Some(1080)).is_err());
+        assert!(resolucion_pedida(Some(1920), Some(4321)).is_err());
+    }
+}
@@ -0,0 +1,476 @@
+// ==========================================
+// SALIDAS Y MONITORES (asignación persistente de pantallas)
+// ==========================================
+// Cada salida (proyector principal, escenario, salidas extra) recuerda en qué
+// monitor va. El monitor se reconoce por nombre, resolución y posición, no por
+// su orden en la lista, que cambia cuando Windows reordena las pantallas.
+use crate::temas::{self, Estilo, EstiloParcial};
+use crate::protocolo::{self, Contenido, EventoProyeccion, Proyeccion};
+use crate::{atajos, transiciones, AppState};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{Emitter, LogicalSize, Manager, PhysicalPosition, State, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+const REVISION_MONITORES: Duration = Duration::from_secs(3);
+pub const SALIDA_PROYECTOR: &str = "proyector";
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct MonitorInfo {
+    id: String,             // Identidad estable: nombre + resolución + posición
+    nombre: Option<String>,
+    pub ancho: u32,
+    pub alto: u32,
+    x: i32,
+    y: i32,
+    escala: f64,
+    principal: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AsignacionSalida {
+    salida: String,
+    monitor_id: Option<String>, // None = ventana
+    conectado: bool,            // El monitor asignado está presente ahora mismo
+}
+
+// Lo que se guarda del monitor para volver a encontrarlo
+struct MonitorGuardado {
+    nombre: Option<String>,
+    ancho: u32,
+    alto: u32,
+    x: i32,
+    y: i32,
+}
+
+// Capas de contenido que puede mostrar una salida
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Capa {
+    Fondo,      // Color, imagen o video de fondo del tema
+    Texto,      // Versículos y cantos
+    Multimedia, // Imágenes, videos y PDF proyectados
+    Avisos,     // Avisos superpuestos
+}
+
+// Salida de audiencia adicional (ej: la TV del lobby). El proyector principal no
+// se configura aquí: siempre muestra todas las capas con el estilo resuelto.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SalidaAudiencia {
+    pub id: String,           // La ventana se llama "salida-<id>"
+    pub nombre: String,
+    pub capas: Vec<Capa>,
+    pub tema_id: Option<i32>, // Tema propio en lugar del resuelto (el ajuste del elemento se sigue aplicando)
+}
+
+impl SalidaAudiencia {
+    fn validar(&self) -> Result<(), String> {
+        if self.id.is_empty() || !self.id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err("El identificador de la salida solo puede tener letras, números, '-' y '_'".to_string());
+        }
+        if self.id == SALIDA_PROYECTOR {
+            return Err("Ese identificador está reservado para el proyector principal".to_string());
+        }
+        if self.nombre.trim().is_empty() {
+            return Err("La salida necesita un nombre".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn tiene(&self, capa: Capa) -> bool {
+        self.capas.contains(&capa)
+    }
+}
+
+pub fn crear_tablas(conn: &Connection) {
+    conn.execute("CREATE TABLE IF NOT EXISTS salidas_monitores (salida TEXT PRIMARY KEY, monitor_nombre TEXT, ancho INTEGER, alto INTEGER, x INTEGER, y INTEGER)", []).unwrap();
+    conn.execute("CREATE TABLE IF NOT EXISTS salidas_audiencia (id TEXT PRIMARY KEY, nombre TEXT NOT NULL, capas TEXT NOT NULL DEFAULT '[]', tema_id INTEGER)", []).unwrap();
+}
+
+// Incluye la posición: dos monitores iguales (mismo modelo y resolución) comparten nombre
+fn identidad(nombre: Option<&str>, ancho: u32, alto: u32, x: i32, y: i32) -> String {
+    format!("{}|{}x{}@{},{}", nombre.unwrap_or(""), ancho, alto, x, y)
+}
+
+pub fn listar_monitores(app: &tauri::AppHandle) -> Vec<MonitorInfo> {
+    let principal = app.primary_monitor().ok().flatten().map(|m| *m.position());
+    app.available_monitors().unwrap_or_default().into_iter().map(|m| {
+        let nombre = m.name().cloned();
+        MonitorInfo {
+            id: identidad(nombre.as_deref(), m.size().width, m.size().height, m.position().x, m.position().y),
+            ancho: m.size().width,
+            alto: m.size().height,
+            x: m.position().x,
+            y: m.position().y,
+            escala: m.scale_factor(),
+            principal: principal == Some(*m.position()),
+            nombre,
+        }
+    }).collect()
+}
+
+fn leer_asignacion(conn: &Connection, salida: &str) -> Option<Option<MonitorGuardado>> {
+    conn.query_row("SELECT monitor_nombre, ancho, alto, x, y FROM salidas_monitores WHERE salida = ?", params![salida], |row| {
+        let ancho: Option<u32> = row.get(1)?;
+        Ok(match ancho {
+            Some(ancho) => Some(MonitorGuardado { nombre: row.get(0)?, ancho, alto: row.get(2)?, x: row.get(3)?, y: row.get(4)? }),
+            None => None,
+        })
+    }).ok()
+}
+
+// El monitor que mejor coincide con el guardado. Con nombre guardado, el nombre y la
+// resolución tienen que coincidir (la posición solo desempata); sin nombre, resolución
+// y posición. Nunca se adivina: si el monitor no está, la salida queda en ventana.
+fn buscar_monitor<'a>(monitores: &'a [MonitorInfo], guardado: &MonitorGuardado) -> Option<&'a MonitorInfo> {
+    let misma_resolucion = |m: &&MonitorInfo| m.ancho == guardado.ancho && m.alto == guardado.alto;
+    let misma_posicion = |m: &&MonitorInfo| m.x == guardado.x && m.y == guardado.y;
+    match &guardado.nombre {
+        Some(nombre) => {
+            let candidatos: Vec<&MonitorInfo> = monitores.iter()
+                .filter(misma_resolucion)
+                .filter(|m| m.nombre.as_deref() == Some(nombre.as_str()))
+                .collect();
+            candidatos.iter().copied().find(misma_posicion).or_else(|| candidatos.first().copied())
+        }
+        None => monitores.iter().filter(misma_resolucion).find(misma_posicion),
+    }
+}
+
+// Monitor donde debe ir la salida. Sin asignación guardada, el proyector usa el
+// segundo monitor como hasta ahora; con "ventana" o monitor ausente, ninguno.
+pub fn monitor_de_salida(app: &tauri::AppHandle, salida: &str) -> Option<MonitorInfo> {
+    let monitores = listar_monitores(app);
+    let asignacion = {
+        let state = app.state::<AppState>();
+        let conn = state.multimedia_db.lock().ok()?;
+        leer_asignacion(&conn, salida)
+    };
+    match asignacion {
+        Some(Some(guardado)) => buscar_monitor(&monitores, &guardado).cloned(),
+        Some(None) => None,
+        None if salida == SALIDA_PROYECTOR && monitores.len() > 1 => monitores.iter().find(|m| !m.principal).cloned(),
+        None => None,
+    }
+}
+
+// Pone la ventana a pantalla completa en su monitor, o en modo ventana si no hay monitor
+pub fn colocar_ventana(app: &tauri::AppHandle, ventana: &tauri::WebviewWindow, salida: &str) {
+    match monitor_de_salida(app, salida) {
+        Some(monitor) => {
+            let _ = ventana.set_fullscreen(false);
+            let _ = ventana.set_position(PhysicalPosition::new(monitor.x, monitor.y));
+            let _ = ventana.set_fullscreen(true);
+        }
+        None => {
+            if ventana.is_fullscreen().unwrap_or(false) {
+                let _ = ventana.set_fullscreen(false);
+                let _ = ventana.set_size(LogicalSize::new(800.0, 600.0));
+                if let Some(principal) = app.primary_monitor().ok().flatten() {
+                    let _ = ventana.set_position(PhysicalPosition::new(principal.position().x + 80, principal.position().y + 80));
+                }
+            }
+        }
+    }
+}
+
+// Abre (o enfoca) la ventana de una salida y la coloca según su asignación
+pub fn abrir_salida(app: &tauri::AppHandle, salida: &str, etiqueta: &str, ruta: &str, titulo: &str) -> Result<(), String> {
+    if let Some(ventana) = app.get_webview_window(etiqueta) {
+        let _ = ventana.set_focus();
+        return Ok(());
+    }
+    let ventana = WebviewWindowBuilder::new(app, etiqueta, WebviewUrl::App(ruta.into()))
+        .title(titulo)
+        .inner_size(800.0, 600.0)
+        .build()
+        .map_err(|e| e.to_string())?;
+    colocar_ventana(app, &ventana, salida);
+    // Los atajos globales solo están activos mientras haya alguna salida abierta
+    atajos::actualizar(app, None);
+    let (app_cierre, etiqueta_cierre) = (app.clone(), etiqueta.to_string());
+    ventana.on_window_event(move |evento| {
+        if let WindowEvent::Destroyed = evento {
+            atajos::actualizar(&app_cierre, Some(&etiqueta_cierre));
+        }
+    });
+    Ok(())
+}
+
+// Ventanas abiertas de cada salida (la etiqueta de la ventana del proyector es "projector")
+pub fn ventanas_de_salidas(app: &tauri::AppHandle) -> Vec<(String, tauri::WebviewWindow)> {
+    app.webview_windows().into_iter().filter_map(|(etiqueta, ventana)| {
+        let salida = if etiqueta == "projector" { SALIDA_PROYECTOR.to_string() } else { etiqueta.strip_prefix("salida-")?.to_string() };
+        Some((salida, ventana))
+    }).collect()
+}
+
+// Revisa periódicamente los monitores: si uno desaparece la salida pasa a modo ventana,
+// y si vuelve se recoloca en él
+pub fn iniciar(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut anteriores = listar_monitores(&app);
+        loop {
+            std::thread::sleep(REVISION_MONITORES);
+            let actuales = listar_monitores(&app);
+            if actuales == anteriores { continue; }
+            anteriores = actuales;
+            for (salida, ventana) in ventanas_de_salidas(&app) {
+                colocar_ventana(&app, &ventana, &salida);
+            }
+            let _ = app.emit("monitors-changed", &anteriores);
+        }
+    });
+}
+
+// ==========================================
+// COMANDOS DE MONITORES
+// ==========================================
+#[tauri::command]
+pub fn get_monitors(app: tauri::AppHandle) -> Vec<MonitorInfo> {
+    listar_monitores(&app)
+}
+
+#[tauri::command]
+pub fn get_output_assignments(app: tauri::AppHandle, state: State<AppState>) -> Result<Vec<AsignacionSalida>, String> {
+    let monitores = listar_monitores(&app);
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare("SELECT salida FROM salidas_monitores ORDER BY salida").map_err(|e| e.to_string())?;
+    let salidas: Vec<String> = stmt.query_map([], |row| row.get(0)).map_err(|e| e.to_string())?.filter_map(Result::ok).collect();
+    Ok(salidas.into_iter().map(|salida| {
+        let guardado = leer_asignacion(&conn, &salida).flatten();
+        // Si el monitor está conectado se devuelve su id actual, que es el que lista get_monitors
+        let actual = guardado.as_ref().and_then(|g| buscar_monitor(&monitores, g));
+        let monitor_id = actual.map(|m| m.id.clone()).or_else(|| guardado.map(|g| identidad(g.nombre.as_deref(), g.ancho, g.alto, g.x, g.y)));
+        AsignacionSalida { monitor_id, salida, conectado: actual.is_some() }
+    }).collect())
+}
+
+// monitor_id = null deja la salida en modo ventana. Si la salida está abierta se recoloca al instante.
+#[tauri::command]
+pub fn assign_output_monitor(app: tauri::AppHandle, salida: String, monitor_id: Option<String>, state: State<AppState>) -> Result<(), String> {
+    let salida = salida.trim().to_string();
+    if salida.is_empty() { return Err("Indique la salida".to_string()); }
+    let monitor = match &monitor_id {
+        Some(id) => Some(listar_monitores(&app).into_iter().find(|m| &m.id == id).ok_or("Ese monitor no está conectado")?),
+        None => None,
+    };
+    {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO salidas_monitores (salida, monitor_nombre, ancho, alto, x, y) VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(salida) DO UPDATE SET monitor_nombre = excluded.monitor_nombre, ancho = excluded.ancho, alto = excluded.alto, x = excluded.x, y = excluded.y",
+            params![salida, monitor.as_ref().and_then(|m| m.nombre.clone()), monitor.as_ref().map(|m| m.ancho), monitor.as_ref().map(|m| m.alto), monitor.as_ref().map(|m| m.x), monitor.as_ref().map(|m| m.y)]
+        ).map_err(|e| e.to_string())?;
+    }
+    if let Some((_, ventana)) = ventanas_de_salidas(&app).into_iter().find(|(s, _)| *s == salida) {
+        colocar_ventana(&app, &ventana, &salida);
+    }
+    Ok(())
+}
+
+// Vuelve al comportamiento automático (segundo monitor para el proyector)
+#[tauri::command]
+pub fn reset_output_monitor(salida: String, state: State<AppState>) -> Result<(), String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM salidas_monitores WHERE salida = ?", params![salida]).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ==========================================
+// SALIDAS DE AUDIENCIA ADICIONALES
+// ==========================================
+fn read_salida(row: &rusqlite::Row) -> rusqlite::Result<SalidaAudiencia> {
+    let capas: String = row.get(2)?;
+    Ok(SalidaAudiencia { id: row.get(0)?, nombre: row.get(1)?, capas: serde_json::from_str(&capas).unwrap_or_default(), tema_id: row.get(3)? })
+}
+
+pub fn listar_salidas(conn: &Connection) -> rusqlite::Result<Vec<SalidaAudiencia>> {
+    let mut stmt = conn.prepare("SELECT id, nombre, capas, tema_id FROM salidas_audiencia ORDER BY nombre")?;
+    let iter = stmt.query_map([], read_salida)?;
+    Ok(iter.filter_map(Result::ok).collect())
+}
+
+fn etiqueta_ventana(id: &str) -> String {
+    format!("salida-{}", id)
+}
+
+// Salidas adicionales que están abiertas ahora mismo, con su configuración
+fn salidas_abiertas(app: &tauri::AppHandle) -> Vec<(SalidaAudiencia, tauri::WebviewWindow)> {
+    let state = app.state::<AppState>();
+    let salidas = match state.multimedia_db.lock() {
+        Ok(conn) => listar_salidas(&conn).unwrap_or_default(),
+        Err(_) => return Vec::new(),
+    };
+    salidas.into_iter().filter_map(|salida| {
+        let ventana = app.get_webview_window(&etiqueta_ventana(&salida.id))?;
+        Some((salida, ventana))
+    }).collect()
+}
+
+// Ventanas de salidas adicionales abiertas que muestran la capa indicada
+pub fn ventanas_con_capa(app: &tauri::AppHandle, capa: Capa) -> Vec<tauri::WebviewWindow> {
+    salidas_abiertas(app).into_iter().filter(|(s, _)| s.tiene(capa)).map(|(_, ventana)| ventana).collect()
+}
+
+fn estilo_de_salida(state: &AppState, salida: &SalidaAudiencia, payload: &serde_json::Value, item: Option<&EstiloParcial>) -> Result<Option<Estilo>, String> {
+    let propio = match salida.tema_id {
+        Some(id) => {
+            let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+            temas::cargar_tema(&conn, id).ok().map(|t| t.estilo)
+        }
+        None => None,
+    };
+    match propio {
+        Some(mut estilo) => {
+            if let Some(cambios) = item { estilo.aplicar(cambios); }
+            Ok(Some(estilo))
+        }
+        None => temas::resolver_estilo(state, payload, item),
+    }
+}
+
+// Lo que ve una salida según sus capas. `None` deja la pantalla vacía (negro).
+fn payload_para(state: &AppState, salida: &SalidaAudiencia, original: &serde_json::Value, item: Option<&EstiloParcial>) -> Result<Option<Proyeccion>, String> {
+    let mut contenido = Contenido::desde_payload(original.clone())?;
+    if !contenido.es_texto() {
+        if !salida.tiene(Capa::Multimedia) { return Ok(None); }
+        return Ok(Some(Proyeccion { contenido, estilo: None, transicion: transiciones::resolver(state, None, item) }));
+    }
+    if !salida.tiene(Capa::Texto) && !salida.tiene(Capa::Fondo) {
+        return Ok(None);
+    }
+    let mut estilo = estilo_de_salida(state, salida, original, item)?;
+    let transicion = transiciones::resolver(state, estilo.as_ref(), item);
+    if !salida.tiene(Capa::Texto) {
+        contenido.quitar_texto();
+    }
+    if let Some(estilo) = estilo.as_mut().filter(|_| !salida.tiene(Capa::Fondo)) {
+        estilo.fondo_color = "#000000".to_string();
+        estilo.fondo_imagen.clear();
+        estilo.fondo_video.clear();
+    }
+    Ok(Some(Proyeccion { contenido, estilo, transicion }))
+}
+
+// Reparte lo proyectado entre las salidas adicionales abiertas (el proyector principal lo recibe aparte)
+pub fn emitir_a_salidas(app: &tauri::AppHandle, original: &serde_json::Value, item: Option<&EstiloParcial>) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    for (salida, ventana) in salidas_abiertas(app) {
+        let payload = payload_para(&state, &salida, original, item)?;
+        protocolo::emitir(&ventana, &EventoProyeccion::Contenido(payload));
+    }
+    Ok(())
+}
+
+// ==========================================
+// COMANDOS DE SALIDAS DE AUDIENCIA
+// ==========================================
+#[tauri::command]
+pub fn get_audience_outputs(state: State<AppState>) -> Result<Vec<SalidaAudiencia>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    listar_salidas(&conn).map_err(|e| e.to_string())
+}
+
+// Crea o actualiza la salida. Si está abierta, recibe enseguida lo que hay en pantalla con la nueva configuración.
+#[tauri::command]
+pub fn save_audience_output(app: tauri::AppHandle, salida: SalidaAudiencia, state: State<AppState>) -> Result<(), String> {
+    salida.validar()?;
+    {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        if let Some(id) = salida.tema_id {
+            temas::cargar_tema(&conn, id).map_err(|_| "El tema indicado no existe".to_string())?;
+        }
+        let capas = serde_json::to_string(&salida.capas).map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO salidas_audiencia (id, nombre, capas, tema_id) VALUES (?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET nombre = excluded.nombre, capas = excluded.capas, tema_id = excluded.tema_id",
+            params![salida.id, salida.nombre.trim(), capas, salida.tema_id]
+        ).map_err(|e| e.to_string())?;
+    }
+    if let Some(ventana) = app.get_webview_window(&etiqueta_ventana(&salida.id)) {
+        let _ = ventana.set_title(salida.nombre.trim());
+        let actual = state.proyeccion.lock().map_err(|e| e.to_string())?.clone();
+        if let Some((original, item)) = actual {
+            protocolo::emitir(&ventana, &EventoProyeccion::Contenido(payload_para(&state, &salida, &original, item.as_ref())?));
+        }
+    }
+    Ok(())
+}
+
+// Borra la salida, su asignación de monitor y cierra su ventana si está abierta
+#[tauri::command]
+pub fn delete_audience_output(app: tauri::AppHandle, id: String, state: State<AppState>) -> Result<(), String> {
+    {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM salidas_audiencia WHERE id = ?", params![id]).map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM salidas_monitores WHERE salida = ?", params![id]).map_err(|e| e.to_string())?;
+    }
+    if let Some(ventana) = app.get_webview_window(&etiqueta_ventana(&id)) {
+        let _ = ventana.close();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn open_audience_output(app: tauri::AppHandle, id: String, state: State<AppState>) -> Result<(), String> {
+    let salida = {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        conn.query_row("SELECT id, nombre, capas, tema_id FROM salidas_audiencia WHERE id = ?", params![id], read_salida)
+            .map_err(|_| "La salida no existe".to_string())?
+    };
+    abrir_salida(&app, &salida.id, &etiqueta_ventana(&salida.id), "/projector", &salida.nombre)
+}
+
+#[tauri::command]
+pub fn close_audience_output(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    if let Some(ventana) = app.get_webview_window(&etiqueta_ventana(&id)) {
+        ventana.close().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(nombre: &str, ancho: u32, alto: u32, x: i32, y: i32) -> MonitorInfo {
+        MonitorInfo { id: identidad(Some(nombre), ancho, alto, x, y), nombre: Some(nombre.to_string()), ancho, alto, x, y, escala: 1.0, principal: x == 0 && y == 0 }
+    }
+
+    fn guardado(nombre: Option<&str>, ancho: u32, alto: u32, x: i32, y: i32) -> MonitorGuardado {
+        MonitorGuardado { nombre: nombre.map(str::to_string), ancho, alto, x, y }
+    }
+
+    #[test]
+    fn monitores_iguales_tienen_identidades_distintas() {
+        let a = monitor("DELL P2419H", 1920, 1080, 1920, 0);
+        let b = monitor("DELL P2419H", 1920, 1080, 3840, 0);
+        assert_ne!(a.id, b.id);
+    }
+
+    #[test]
+    fn prefiere_nombre_resolucion_y_posicion() {
+        let monitores = vec![
+            monitor("Generic PnP", 1920, 1080, 0, 0),
+            monitor("DELL P2419H", 1920, 1080, 1920, 0),
+            monitor("DELL P2419H", 1920, 1080, 3840, 0),
+        ];
+        let encontrado = buscar_monitor(&monitores, &guardado(Some("DELL P2419H"), 1920, 1080, 3840, 0)).unwrap();
+        assert_eq!(encontrado.x, 3840);
+    }
+
+    #[test]
+    fn exige_el_nombre_guardado() {
+        let monitores = vec![monitor("Generic PnP", 1920, 1080, 0, 0), monitor("EPSON PJ", 1280, 800, 1920, 0)];
+        // El proyector cambió de posición: se reconoce por nombre y resolución
+        assert_eq!(buscar_monitor(&monitores, &guardado(Some("EPSON PJ"), 1280, 800, 3000, 0)).unwrap().x, 1920);
+        // Sin nombre guardado: resolución y posición
+        assert_eq!(buscar_monitor(&monitores, &guardado(None, 1920, 1080, 0, 0)).unwrap().x, 0);
+        // Otro nombre: aunque la resolución coincida no se adivina, queda en ventana
+        assert!(buscar_monitor(&monitores, &guardado(Some("Otro"), 1280, 800, 500, 0)).is_none());
+        assert!(buscar_monitor(&monitores, &guardado(Some("Otro"), 1280, 800, 1920, 0)).is_none());
+        // Resolución que no está
+        assert!(buscar_monitor(&monitores, &guardado(Some("EPSON PJ"), 1024, 768, 1920, 0)).is_none());
+    }
+}
@@ -0,0 +1,235 @@
+// ==========================================
+// SUPERPOSICIÓN PARA TRANSMISIÓN (letra sobre la cámara)
+// ==========================================
+// Servidor HTTP local para fuentes de navegador de OBS o vMix. Solo muestra el
+// texto, sobre fondo transparente o de color para chroma, y lee lo mismo que
+// está viendo el público (`AppState.proyeccion` y el estado de la pantalla).
+use crate::temas::{self, Estilo};
+use crate::{guardar_config, leer_config, pantalla, parse_color_hex, protocolo, AppState};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{Manager, State};
+
+const CONFIG_SUPERPOSICION: &str = "superposicion";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Disposicion {
+    Completa,       // Texto centrado en toda la pantalla
+    TercioInferior, // Franja en la parte de abajo
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ConfigSuperposicion {
+    pub activo: bool,
+    pub puerto: u16,
+    pub fondo: String,            // "transparente" o un color hex para chroma (ej: "#00ff00")
+    pub disposicion: Disposicion,
+    pub referencia: bool,         // Mostrar la cita o el título del canto
+}
+
+impl Default for ConfigSuperposicion {
+    fn default() -> Self {
+        ConfigSuperposicion { activo: false, puerto: 7320, fondo: "transparente".to_string(), disposicion: Disposicion::TercioInferior, referencia: true }
+    }
+}
+
+impl ConfigSuperposicion {
+    fn validar(&self) -> Result<(), String> {
+        if self.puerto < 1024 {
+            return Err("Use un puerto entre 1024 y 65535".to_string());
+        }
+        if self.fondo != "transparente" {
+            parse_color_hex(&self.fondo)?;
+        }
+        Ok(())
+    }
+}
+
+// Lo que consulta la página cada pocos milisegundos
+#[derive(Serialize)]
+struct EstadoSuperposicion {
+    texto: String,
+    referencia: Option<String>,
+    estilo: Option<Estilo>,
+    fondo: String,
+    disposicion: Disposicion,
+}
+
+pub fn leer_configuracion(state: &AppState) -> ConfigSuperposicion {
+    state.multimedia_db.lock().ok()
+        .and_then(|conn| leer_config(&conn, CONFIG_SUPERPOSICION))
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+// Cita igual que la muestra el proyector: título para los cantos, "Libro 3:16 (RVR)" para la Biblia
+fn referencia(payload: &serde_json::Value) -> Option<String> {
+    let libro = payload["libro"].as_str()?;
+    if payload["capitulo"].as_i64() == Some(0) {
+        return Some(libro.to_string());
+    }
+    let version = payload["versionName"].as_str().unwrap_or("");
+    let corta = match (version.find('('), version.find(')')) {
+        (Some(i), Some(f)) if f > i => &version[i + 1..f],
+        _ => version,
+    };
+    let valor = |v: &serde_json::Value| v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string());
+    Some(format!("{} {}:{} ({})", libro, valor(&payload["capitulo"]), valor(&payload["versiculo"]), corta))
+}
+
+fn estado_actual(state: &AppState, config: &ConfigSuperposicion) -> EstadoSuperposicion {
+    // Lo mismo que ve el público: nada en negro o con el logo, lo congelado si está congelado
+    let sin_texto = state.pantalla.lock().is_ok_and(|p| p.modo == pantalla::ModoPantalla::SinTexto);
+    let actual = if sin_texto { None } else { pantalla::visible(state) };
+    let mut estado = EstadoSuperposicion { texto: String::new(), referencia: None, estilo: None, fondo: config.fondo.clone(), disposicion: config.disposicion };
+    let Some((payload, item)) = actual else { return estado };
+    // Imágenes, videos y PDF no van sobre la cámara
+    if !protocolo::es_texto(&payload) {
+        return estado;
+    }
+    estado.texto = payload["texto"].as_str().unwrap_or("").to_string();
+    if config.referencia { estado.referencia = referencia(&payload); }
+    estado.estilo = temas::resolver_estilo(state, &payload, item.as_ref()).ok().flatten();
+    estado
+}
+
+fn responder(peticion: tiny_http::Request, cuerpo: String, tipo: &str) {
+    let cabeceras = [
+        tiny_http::Header::from_bytes("Content-Type", tipo).unwrap(),
+        tiny_http::Header::from_bytes("Cache-Control", "no-store").unwrap(),
+        tiny_http::Header::from_bytes("Access-Control-Allow-Origin", "*").unwrap(),
+    ];
+    let mut respuesta = tiny_http::Response::from_string(cuerpo);
+    for cabecera in cabeceras { respuesta.add_header(cabecera); }
+    let _ = peticion.respond(respuesta);
+}
+
+fn atender(app: &tauri::AppHandle, peticion: tiny_http::Request) {
+    let ruta = peticion.url().split('?').next().unwrap_or("/").to_string();
+    let state = app.state::<AppState>();
+    match ruta.as_str() {
+        "/" => responder(peticion, PAGINA.to_string(), "text/html; charset=utf-8"),
+        "/estado" => {
+            let estado = estado_actual(&state, &leer_configuracion(&state));
+            responder(peticion, serde_json::to_string(&estado).unwrap_or_default(), "application/json; charset=utf-8");
+        }
+        _ => { let _ = peticion.respond(tiny_http::Response::empty(404)); }
+    }
+}
+
+// Detiene el servidor anterior (si hay) y arranca uno nuevo si la configuración lo pide
+pub fn reiniciar(app: &tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    if let Some(anterior) = state.superposicion.lock().map_err(|e| e.to_string())?.take() {
+        anterior.unblock();
+    }
+    let config = leer_configuracion(&state);
+    if !config.activo { return Ok(()); }
+
+    // Solo en 127.0.0.1: la fuente de navegador corre en la misma máquina
+    let servidor = Arc::new(tiny_http::Server::http(("127.0.0.1", config.puerto))
+        .map_err(|e| format!("No se pudo abrir el puerto {}: {}", config.puerto, e))?);
+    *state.superposicion.lock().map_err(|e| e.to_string())? = Some(servidor.clone());
+    let app = app.clone();
+    std::thread::spawn(move || {
+        for peticion in servidor.incoming_requests() {
+            atender(&app, peticion);
+        }
+    });
+    Ok(())
+}
+
+// Se llama una vez al iniciar la aplicación
+pub fn iniciar(app: tauri::AppHandle) {
+    if let Err(e) = reiniciar(&app) {
+        println!("No se pudo iniciar la superposición para transmisión: {}", e);
+    }
+}
+
+// ==========================================
+// COMANDOS DE SUPERPOSICIÓN
+// ==========================================
+#[tauri::command]
+pub fn get_overlay_config(state: State<AppState>) -> ConfigSuperposicion {
+    leer_configuracion(&state)
+}
+
+#[tauri::command]
+pub fn set_overlay_config(app: tauri::AppHandle, config: ConfigSuperposicion, state: State<AppState>) -> Result<(), String> {
+    config.validar()?;
+    let anterior = leer_configuracion(&state);
+    {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        guardar_config(&conn, CONFIG_SUPERPOSICION, &serde_json::to_string(&config).map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+    }
+    // Fondo y disposición se leen en cada consulta; solo hace falta reiniciar si cambia el servidor
+    if anterior.activo != config.activo || anterior.puerto != config.puerto {
+        reiniciar(&app)?;
+    }
+    Ok(())
+}
+
+// Dirección para pegar en la fuente de navegador (None si está apagada)
+#[tauri::command]
+pub fn get_overlay_url(state: State<AppState>) -> Result<Option<String>, String> {
+    let activo = state.superposicion.lock().map_err(|e| e.to_string())?.is_some();
+    let config = leer_configuracion(&state);
+    Ok(activo.then(|| format!("http://127.0.0.1:{}/", config.puerto)))
+}
+
+// Página de la fuente de navegador. `?fondo=00ff00` y `?disposicion=completa` permiten
+// usar varias fuentes con distinta configuración a la vez.
+const PAGINA: &str = r##"<!DOCTYPE html>
+<html><head><meta charset="utf-8"><title>Superposición</title>
+<style>
+  html, body { margin: 0; width: 100%; height: 100%; overflow: hidden; background: transparent; font-family: sans-serif; }
+  #caja { position: absolute; left: 5%; right: 5%; display: flex; flex-direction: column; justify-content: center; box-sizing: border-box; padding: 2vh 3vw; transition: opacity .3s; }
+  #caja.completa { top: 5%; bottom: 5%; }
+  #caja.tercio_inferior { bottom: 4%; height: 28%; background: rgba(0, 0, 0, .55); border-radius: 1vh; }
+  #caja.chroma.tercio_inferior { background: transparent; }
+  #texto { white-space: pre-line; overflow-wrap: break-word; line-height: 1.25; font-weight: bold; }
+  #referencia { margin-top: 1vh; font-size: 3.5vh; opacity: .85; }
+</style></head>
+<body><div id="caja"><div id="texto"></div><div id="referencia"></div></div>
+<script>
+  const q = new URLSearchParams(location.search);
+  const caja = document.getElementById('caja'), texto = document.getElementById('texto'), ref = document.getElementById('referencia');
+  let anterior = '';
+  function ajustar() {
+    let min = 12, max = 200;
+    while (min <= max) {
+      const medio = (min + max) >> 1;
+      texto.style.fontSize = medio + 'px';
+      if (caja.scrollHeight <= caja.clientHeight && caja.scrollWidth <= caja.clientWidth) min = medio + 1; else max = medio - 1;
+    }
+    texto.style.fontSize = max + 'px';
+  }
+  function pintar(e) {
+    const fondo = q.get('fondo') ? (q.get('fondo') === 'transparente' ? 'transparente' : '#' + q.get('fondo').replace('#', '')) : e.fondo;
+    const disposicion = q.get('disposicion') || e.disposicion;
+    document.body.style.background = fondo === 'transparente' ? 'transparent' : fondo;
+    caja.className = disposicion + (fondo === 'transparente' ? '' : ' chroma');
+    caja.style.opacity = e.texto ? 1 : 0;
+    const s = e.estilo || {};
+    texto.textContent = e.texto;
+    texto.style.color = ref.style.color = s.textColor || '#ffffff';
+    texto.style.fontFamily = ref.style.fontFamily = /\.(ttf|otf)$/i.test(s.fontFamily || '') ? 'sans-serif' : (s.fontFamily || 'sans-serif');
+    texto.style.textAlign = ref.style.textAlign = s.align || 'center';
+    texto.style.textShadow = s.shadow ? `${s.shadow.x}px ${s.shadow.y}px ${s.shadow.blur}px ${s.shadow.color}` : '0 2px 6px rgba(0,0,0,.8)';
+    texto.style.webkitTextStroke = s.outline ? `${s.outline.width}px ${s.outline.color}` : '';
+    ref.textContent = e.referencia || '';
+    if (s.fontSize && disposicion === 'completa') texto.style.fontSize = s.fontSize + 'px'; else ajustar();
+  }
+  async function consultar() {
+    try {
+      const r = await fetch('/estado');
+      const json = await r.text();
+      if (json !== anterior) { anterior = json; pintar(JSON.parse(json)); }
+    } catch (_) {}
+    setTimeout(consultar, 250);
+  }
+  window.addEventListener('resize', () => anterior && pintar(JSON.parse(anterior)));
+  consultar();
+</script></body></html>"##;
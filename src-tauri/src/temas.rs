@@ -0,0 +1,502 @@
+// ==========================================
+// TEMAS DE PROYECCIÓN (estilos guardados en la base de datos)
+// ==========================================
+use crate::protocolo::{self, EventoProyeccion};
+use crate::transiciones::Transiciones;
+use crate::{guardar_config, leer_config, parse_color_hex, AppState};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{Manager, State};
+
+const CONFIG_ESTILOS: &str = "estilos_proyector";
+
+// Las claves JSON son las que ya usa el proyector (bgColor, textColor, margins...)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Estilo {
+    #[serde(rename = "fontFamily")]
+    pub fuente: String,
+    #[serde(rename = "fontSize")]
+    pub tamano: Option<f32>, // Píxeles. Sin valor el proyector ajusta el texto automáticamente
+    #[serde(rename = "textColor")]
+    pub color: String,
+    #[serde(rename = "shadow")]
+    pub sombra: Option<Sombra>,
+    #[serde(rename = "outline")]
+    pub contorno: Option<Contorno>,
+    #[serde(rename = "align")]
+    pub alineacion: Alineacion,
+    #[serde(rename = "bgColor")]
+    pub fondo_color: String,
+    #[serde(rename = "bgImage")]
+    pub fondo_imagen: String, // "" = sin imagen
+    #[serde(rename = "bgVideo")]
+    pub fondo_video: String,  // "" = sin video
+    #[serde(rename = "margins")]
+    pub margenes: Margenes,
+    #[serde(rename = "transitions")]
+    pub transiciones: Transiciones, // Al cambiar texto y fondo (ver transiciones.rs)
+}
+
+impl Default for Estilo {
+    fn default() -> Self {
+        Estilo {
+            fuente: "sans-serif".to_string(),
+            tamano: None,
+            color: "#ffffff".to_string(),
+            sombra: None,
+            contorno: None,
+            alineacion: Alineacion::Center,
+            fondo_color: "#000000".to_string(),
+            fondo_imagen: String::new(),
+            fondo_video: String::new(),
+            margenes: Margenes::default(),
+            transiciones: Transiciones::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Sombra {
+    pub color: String,
+    #[serde(rename = "blur")]
+    pub desenfoque: f32,
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Contorno {
+    pub color: String,
+    #[serde(rename = "width")]
+    pub ancho: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Alineacion { Left, Center, Right }
+
+// Porcentaje de la pantalla, igual que el evento `update-margins`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Margenes { pub top: f32, pub right: f32, pub bottom: f32, pub left: f32 }
+
+const FONDO_TRANSPARENTE: &str = "transparent";
+
+impl Estilo {
+    pub fn validar(&self) -> Result<(), String> {
+        parse_color_hex(&self.color)?;
+        // El panel manda "transparent" cuando el fondo es una imagen o un video
+        if self.fondo_color != FONDO_TRANSPARENTE { parse_color_hex(&self.fondo_color)?; }
+        if let Some(sombra) = &self.sombra {
+            parse_color_hex(&sombra.color)?;
+            if !(0.0..=100.0).contains(&sombra.desenfoque) { return Err("El desenfoque de la sombra debe estar entre 0 y 100".to_string()); }
+        }
+        if let Some(contorno) = &self.contorno {
+            parse_color_hex(&contorno.color)?;
+            if !(0.0..=20.0).contains(&contorno.ancho) { return Err("El contorno debe tener entre 0 y 20 píxeles".to_string()); }
+        }
+        if let Some(tamano) = self.tamano {
+            if !(10.0..=400.0).contains(&tamano) { return Err("El tamaño de letra debe estar entre 10 y 400".to_string()); }
+        }
+        let m = self.margenes;
+        if [m.top, m.right, m.bottom, m.left].iter().any(|v| !(0.0..=45.0).contains(v)) {
+            return Err("Los márgenes deben estar entre 0% y 45%".to_string());
+        }
+        if !self.fondo_imagen.is_empty() && !self.fondo_video.is_empty() {
+            return Err("El fondo puede ser una imagen o un video, no ambos".to_string());
+        }
+        self.transiciones.validar()
+    }
+}
+
+// Cambios sueltos sobre un estilo (solo se aplican los campos presentes)
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct EstiloParcial {
+    // Tema que reemplaza al resuelto antes de aplicar el resto de los campos
+    #[serde(rename = "themeId", skip_serializing_if = "Option::is_none")]
+    pub tema_id: Option<i32>,
+    #[serde(rename = "fontFamily", skip_serializing_if = "Option::is_none")]
+    pub fuente: Option<String>,
+    #[serde(rename = "fontSize", skip_serializing_if = "Option::is_none")]
+    pub tamano: Option<f32>,
+    #[serde(rename = "textColor", skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(rename = "shadow", skip_serializing_if = "Option::is_none")]
+    pub sombra: Option<Sombra>,
+    #[serde(rename = "outline", skip_serializing_if = "Option::is_none")]
+    pub contorno: Option<Contorno>,
+    #[serde(rename = "align", skip_serializing_if = "Option::is_none")]
+    pub alineacion: Option<Alineacion>,
+    #[serde(rename = "bgColor", skip_serializing_if = "Option::is_none")]
+    pub fondo_color: Option<String>,
+    #[serde(rename = "bgImage", skip_serializing_if = "Option::is_none")]
+    pub fondo_imagen: Option<String>,
+    #[serde(rename = "bgVideo", skip_serializing_if = "Option::is_none")]
+    pub fondo_video: Option<String>,
+    #[serde(rename = "margins", skip_serializing_if = "Option::is_none")]
+    pub margenes: Option<Margenes>,
+    #[serde(rename = "transitions", skip_serializing_if = "Option::is_none")]
+    pub transiciones: Option<Transiciones>,
+}
+
+impl Estilo {
+    // El `tema_id` de los cambios no se aplica aquí: lo resuelve `resolver_estilo`, que tiene la base
+    pub fn aplicar(&mut self, cambios: &EstiloParcial) {
+        let c = cambios.clone();
+        if let Some(v) = c.fuente { self.fuente = v; }
+        if let Some(v) = c.tamano { self.tamano = Some(v); }
+        if let Some(v) = c.color { self.color = v; }
+        if let Some(v) = c.sombra { self.sombra = Some(v); }
+        if let Some(v) = c.contorno { self.contorno = Some(v); }
+        if let Some(v) = c.alineacion { self.alineacion = v; }
+        if let Some(v) = c.fondo_color { self.fondo_color = v; }
+        if let Some(v) = c.margenes { self.margenes = v; }
+        if let Some(v) = c.transiciones { self.transiciones = v; }
+        // Igual que en el panel de estilos: un fondo nuevo reemplaza al otro tipo de fondo
+        if let Some(v) = c.fondo_imagen {
+            if !v.is_empty() { self.fondo_video.clear(); }
+            self.fondo_imagen = v;
+        }
+        if let Some(v) = c.fondo_video {
+            if !v.is_empty() { self.fondo_imagen.clear(); }
+            self.fondo_video = v;
+        }
+    }
+}
+
+impl EstiloParcial {
+    pub fn validar(&self) -> Result<(), String> {
+        if self.fondo_imagen.as_deref().is_some_and(|v| !v.is_empty()) && self.fondo_video.as_deref().is_some_and(|v| !v.is_empty()) {
+            return Err("El fondo puede ser una imagen o un video, no ambos".to_string());
+        }
+        let mut prueba = Estilo::default();
+        prueba.aplicar(self);
+        prueba.validar()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Tema { pub id: i32, pub nombre: String, pub estilo: Estilo }
+
+// Estilos que se envían al proyector con `update-styles`
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct EstilosProyector { pub biblia: Estilo, pub cantos: Estilo }
+
+// Ámbitos que tienen un tema por defecto global
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Ambito { Biblia, Cantos }
+
+impl Ambito {
+    fn clave(self) -> &'static str {
+        match self {
+            Ambito::Biblia => "tema_biblia",
+            Ambito::Cantos => "tema_cantos",
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Default)]
+pub struct TemasPorDefecto {
+    biblia: Option<i32>,
+    cantos: Option<i32>,
+    versiones: HashMap<String, i32>,  // Versión de la Biblia -> tema
+    categorias: HashMap<String, i32>, // Categoría de cantos -> tema
+}
+
+pub fn crear_tablas(conn: &Connection) {
+    conn.execute("CREATE TABLE IF NOT EXISTS temas (id INTEGER PRIMARY KEY AUTOINCREMENT, nombre TEXT NOT NULL, estilo TEXT NOT NULL)", []).unwrap();
+    // Tema de cada categoría de cantos (la categoría es texto libre en cantos.db)
+    conn.execute("CREATE TABLE IF NOT EXISTS temas_categorias (categoria TEXT PRIMARY KEY, tema_id INTEGER NOT NULL)", []).unwrap();
+}
+
+// Referencias a temas desde cantos.db y biblias.db
+pub fn migrar_referencias(conn_cantos: &Connection, conn_biblia: &Connection) {
+    let _ = conn_cantos.execute("ALTER TABLE cantos ADD COLUMN tema_id INTEGER", []);
+    let _ = conn_cantos.execute("ALTER TABLE cantos ADD COLUMN estilo TEXT", []); // EstiloParcial en JSON
+    let _ = conn_biblia.execute("ALTER TABLE versiones ADD COLUMN tema_id INTEGER", []);
+}
+
+fn read_tema(row: &rusqlite::Row) -> rusqlite::Result<Tema> {
+    let estilo: String = row.get(2)?;
+    Ok(Tema {
+        id: row.get(0)?,
+        nombre: row.get(1)?,
+        // Un estilo guardado por una versión anterior conserva lo que se pueda leer
+        estilo: serde_json::from_str(&estilo).unwrap_or_default(),
+    })
+}
+
+pub fn cargar_tema(conn: &Connection, id: i32) -> rusqlite::Result<Tema> {
+    conn.query_row("SELECT id, nombre, estilo FROM temas WHERE id = ?", params![id], read_tema)
+}
+
+// ==========================================
+// COMANDOS DE TEMAS
+// ==========================================
+#[tauri::command]
+pub fn get_all_themes(state: State<AppState>) -> Result<Vec<Tema>, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare("SELECT id, nombre, estilo FROM temas ORDER BY nombre").map_err(|e| e.to_string())?;
+    let iter = stmt.query_map([], read_tema).map_err(|e| e.to_string())?;
+    Ok(iter.filter_map(Result::ok).collect())
+}
+
+#[tauri::command]
+pub fn add_theme(nombre: String, estilo: Estilo, state: State<AppState>) -> Result<Tema, String> {
+    let nombre = nombre.trim();
+    if nombre.is_empty() { return Err("El tema necesita un nombre".to_string()); }
+    estilo.validar()?;
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let json = serde_json::to_string(&estilo).map_err(|e| e.to_string())?;
+    conn.execute("INSERT INTO temas (nombre, estilo) VALUES (?, ?)", params![nombre, json]).map_err(|e| e.to_string())?;
+    Ok(Tema { id: conn.last_insert_rowid() as i32, nombre: nombre.to_string(), estilo })
+}
+
+#[tauri::command]
+pub fn update_theme(id: i32, nombre: String, estilo: Estilo, state: State<AppState>) -> Result<(), String> {
+    let nombre = nombre.trim();
+    if nombre.is_empty() { return Err("El tema necesita un nombre".to_string()); }
+    estilo.validar()?;
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let json = serde_json::to_string(&estilo).map_err(|e| e.to_string())?;
+    let cambios = conn.execute("UPDATE temas SET nombre = ?, estilo = ? WHERE id = ?", params![nombre, json, id]).map_err(|e| e.to_string())?;
+    if cambios == 0 { return Err("El tema no existe".to_string()); }
+    Ok(())
+}
+
+// Borra el tema y quita las referencias que lo usaban
+#[tauri::command]
+pub fn delete_theme(id: i32, state: State<AppState>) -> Result<(), String> {
+    {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM temas WHERE id = ?", params![id]).map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM configuracion WHERE clave IN (?, ?) AND valor = ?", params![Ambito::Biblia.clave(), Ambito::Cantos.clave(), id.to_string()]).map_err(|e| e.to_string())?;
+        conn.execute("DELETE FROM temas_categorias WHERE tema_id = ?", params![id]).map_err(|e| e.to_string())?;
+    }
+    {
+        let conn = state.cantos_db.lock().map_err(|e| e.to_string())?;
+        conn.execute("UPDATE cantos SET tema_id = NULL WHERE tema_id = ?", params![id]).map_err(|e| e.to_string())?;
+    }
+    let conn = state.biblias_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("UPDATE versiones SET tema_id = NULL WHERE tema_id = ?", params![id]).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn verificar_tema(state: &AppState, tema_id: Option<i32>) -> Result<(), String> {
+    if let Some(id) = tema_id {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        cargar_tema(&conn, id).map_err(|_| "El tema no existe".to_string())?;
+    }
+    Ok(())
+}
+
+// tema_id = null quita el tema propio del canto
+#[tauri::command]
+pub fn set_song_theme(canto_id: i32, tema_id: Option<i32>, state: State<AppState>) -> Result<(), String> {
+    verificar_tema(&state, tema_id)?;
+    let conn = state.cantos_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("UPDATE cantos SET tema_id = ? WHERE id = ?", params![tema_id, canto_id]).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// Cambios propios del canto sobre el tema que le toque (null = sin cambios)
+#[tauri::command]
+pub fn set_song_style(canto_id: i32, estilo: Option<EstiloParcial>, state: State<AppState>) -> Result<(), String> {
+    let json = match estilo.filter(|e| *e != EstiloParcial::default()) {
+        Some(estilo) => {
+            estilo.validar()?;
+            verificar_tema(&state, estilo.tema_id)?;
+            Some(serde_json::to_string(&estilo).map_err(|e| e.to_string())?)
+        }
+        None => None,
+    };
+    let conn = state.cantos_db.lock().map_err(|e| e.to_string())?;
+    conn.execute("UPDATE cantos SET estilo = ? WHERE id = ?", params![json, canto_id]).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_song_style(canto_id: i32, state: State<AppState>) -> Result<Option<EstiloParcial>, String> {
+    let conn = state.cantos_db.lock().map_err(|e| e.to_string())?;
+    let json: Option<String> = conn.query_row("SELECT estilo FROM cantos WHERE id = ?", params![canto_id], |row| row.get(0)).map_err(|e| e.to_string())?;
+    Ok(json.and_then(|j| serde_json::from_str(&j).ok()))
+}
+
+#[tauri::command]
+pub fn set_category_theme(categoria: String, tema_id: Option<i32>, state: State<AppState>) -> Result<(), String> {
+    verificar_tema(&state, tema_id)?;
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    match tema_id {
+        Some(id) => conn.execute("INSERT INTO temas_categorias (categoria, tema_id) VALUES (?, ?) ON CONFLICT(categoria) DO UPDATE SET tema_id = excluded.tema_id", params![categoria, id]),
+        None => conn.execute("DELETE FROM temas_categorias WHERE categoria = ?", params![categoria]),
+    }.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_version_theme(version: String, tema_id: Option<i32>, state: State<AppState>) -> Result<(), String> {
+    verificar_tema(&state, tema_id)?;
+    let conn = state.biblias_db.lock().map_err(|e| e.to_string())?;
+    let cambios = conn.execute("UPDATE versiones SET tema_id = ? WHERE nombre = ?", params![tema_id, version]).map_err(|e| e.to_string())?;
+    if cambios == 0 { return Err(format!("La versión {} no existe", version)); }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_default_theme(ambito: Ambito, tema_id: Option<i32>, state: State<AppState>) -> Result<(), String> {
+    verificar_tema(&state, tema_id)?;
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    match tema_id {
+        Some(id) => guardar_config(&conn, ambito.clave(), &id.to_string()).map_err(|e| e.to_string())?,
+        None => { conn.execute("DELETE FROM configuracion WHERE clave = ?", params![ambito.clave()]).map_err(|e| e.to_string())?; }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_default_themes(state: State<AppState>) -> Result<TemasPorDefecto, String> {
+    let mut defecto = TemasPorDefecto::default();
+    {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        defecto.biblia = leer_config(&conn, Ambito::Biblia.clave()).and_then(|v| v.parse().ok());
+        defecto.cantos = leer_config(&conn, Ambito::Cantos.clave()).and_then(|v| v.parse().ok());
+        let mut stmt = conn.prepare("SELECT categoria, tema_id FROM temas_categorias").map_err(|e| e.to_string())?;
+        let iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).map_err(|e| e.to_string())?;
+        defecto.categorias = iter.filter_map(Result::ok).collect();
+    }
+    let conn = state.biblias_db.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare("SELECT nombre, tema_id FROM versiones WHERE tema_id IS NOT NULL").map_err(|e| e.to_string())?;
+    let iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).map_err(|e| e.to_string())?;
+    defecto.versiones = iter.filter_map(Result::ok).collect();
+    Ok(defecto)
+}
+
+// ==========================================
+// ESTILOS EN VIVO DEL PROYECTOR
+// ==========================================
+// Además de enviarlos al proyector se guardan, para no perderlos al reiniciar
+#[tauri::command]
+pub fn trigger_style_update(app: tauri::AppHandle, styles: EstilosProyector, state: State<AppState>) -> Result<(), String> {
+    styles.biblia.validar()?;
+    styles.cantos.validar()?;
+    {
+        let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+        guardar_config(&conn, CONFIG_ESTILOS, &serde_json::to_string(&styles).map_err(|e| e.to_string())?).map_err(|e| e.to_string())?;
+    }
+    // Congelado no se toca la pantalla: el estilo nuevo sale al descongelar
+    if crate::pantalla::congelada(&state) { return Ok(()); }
+    if let Some(projector_window) = app.get_webview_window("projector") {
+        protocolo::emitir(&projector_window, &EventoProyeccion::Estilos(styles.clone()));
+        // Lo que está en pantalla lleva su estilo ya resuelto: se recalcula con la nueva base
+        let actual = state.proyeccion.lock().map_err(|e| e.to_string())?.clone();
+        if let Some((payload, item)) = actual {
+            if let Some(estilo) = resolver_estilo(&state, &payload, item.as_ref())? {
+                protocolo::emitir(&projector_window, &EventoProyeccion::EstiloEnVivo(estilo));
+            }
+        }
+    }
+    // Las salidas adicionales sin tema propio también dependen de la base
+    let actual = state.proyeccion.lock().map_err(|e| e.to_string())?.clone();
+    if let Some((payload, item)) = actual {
+        crate::salidas::emitir_a_salidas(&app, &payload, item.as_ref())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_projector_styles(state: State<AppState>) -> Result<EstilosProyector, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    Ok(leer_config(&conn, CONFIG_ESTILOS).and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default())
+}
+
+// ==========================================
+// RESOLUCIÓN DEL ESTILO EFECTIVO
+// ==========================================
+// Orden: global (tema por defecto o estilos en vivo) -> categoría / versión -> canto -> cambios del
+// canto -> cambios del elemento. Los cambios con `themeId` parten de ese tema en lugar del anterior.
+// Devuelve None para imágenes, videos y PDFs, que no llevan estilo de texto.
+pub fn resolver_estilo(state: &AppState, payload: &serde_json::Value, item: Option<&EstiloParcial>) -> Result<Option<Estilo>, String> {
+    if !protocolo::es_texto(payload) {
+        return Ok(None);
+    }
+    let es_canto = payload["capitulo"].as_i64() == Some(0);
+    let ambito = if es_canto { Ambito::Cantos } else { Ambito::Biblia };
+
+    // Datos del canto o de la versión (sin bloquear dos bases a la vez)
+    let mut tema_intermedio: Option<i32> = None;
+    let mut tema_canto: Option<i32> = None;
+    let mut estilo_canto: Option<EstiloParcial> = None;
+    let mut categoria: Option<String> = None;
+    if let Some(canto_id) = payload["canto_id"].as_i64().filter(|_| es_canto) {
+        let conn = state.cantos_db.lock().map_err(|e| e.to_string())?;
+        let fila: Option<(Option<String>, Option<i32>, Option<String>)> = conn
+            .query_row("SELECT categoria, tema_id, estilo FROM cantos WHERE id = ?", params![canto_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .ok();
+        if let Some((cat, tema, estilo)) = fila {
+            categoria = cat;
+            tema_canto = tema;
+            estilo_canto = estilo.and_then(|j| serde_json::from_str(&j).ok());
+        }
+    } else if let Some(version) = payload["versionName"].as_str().filter(|_| !es_canto) {
+        let conn = state.biblias_db.lock().map_err(|e| e.to_string())?;
+        tema_intermedio = conn.query_row("SELECT tema_id FROM versiones WHERE nombre = ?", params![version], |row| row.get(0)).ok().flatten();
+    }
+
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    let tema = |id: Option<i32>| id.and_then(|id| cargar_tema(&conn, id).ok()).map(|t| t.estilo);
+
+    let mut estilo = match tema(leer_config(&conn, ambito.clave()).and_then(|v| v.parse().ok())) {
+        Some(estilo) => estilo,
+        None => {
+            let vivos: EstilosProyector = leer_config(&conn, CONFIG_ESTILOS).and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default();
+            if es_canto { vivos.cantos } else { vivos.biblia }
+        }
+    };
+    if let Some(cat) = categoria {
+        tema_intermedio = conn.query_row("SELECT tema_id FROM temas_categorias WHERE categoria = ?", params![cat], |row| row.get(0)).ok();
+    }
+    for capa in [tema(tema_intermedio), tema(tema_canto)].into_iter().flatten() {
+        estilo = capa;
+    }
+    // Primero los cambios del canto y luego los del elemento; cada uno puede traer su tema
+    for cambios in [estilo_canto.as_ref(), item].into_iter().flatten() {
+        if let Some(propio) = tema(cambios.tema_id) {
+            estilo = propio;
+        }
+        estilo.aplicar(cambios);
+    }
+    Ok(Some(estilo))
+}
+
+// Para la vista previa del panel de control
+#[tauri::command]
+pub fn get_effective_style(verse: serde_json::Value, estilo: Option<EstiloParcial>, state: State<AppState>) -> Result<Option<Estilo>, String> {
+    resolver_estilo(&state, &verse, estilo.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Lo que manda el panel al elegir una imagen o un video de fondo
+    #[test]
+    fn acepta_fondo_transparente_con_imagen_o_video() {
+        let estilo: Estilo = serde_json::from_value(serde_json::json!({
+            "fontFamily": "Arial", "textColor": "#ffffff", "bgColor": "transparent",
+            "bgImage": "C:/fondos/cruz.jpg", "bgVideo": ""
+        })).unwrap();
+        assert_eq!(estilo.validar(), Ok(()));
+
+        let parcial = EstiloParcial { fondo_color: Some("transparent".to_string()), fondo_video: Some("C:/fondos/olas.mp4".to_string()), ..Default::default() };
+        assert_eq!(parcial.validar(), Ok(()));
+    }
+
+    #[test]
+    fn rechaza_colores_invalidos() {
+        let estilo = Estilo { fondo_color: "azul".to_string(), ..Default::default() };
+        assert!(estilo.validar().is_err());
+    }
+}
@@ -0,0 +1,289 @@
+// ==========================================
+// TEMPORIZADORES Y RELOJES (cuenta regresiva, cronómetro, reloj)
+// ==========================================
+// El tiempo se lleva en el backend: una ventana minimizada o en segundo plano
+// puede frenar sus `setTimeout`, pero no afecta al temporizador. Cada
+// temporizador tiene nombre y envía `timer-tick` solo a las ventanas suscritas.
+use crate::atajos::AccionAtajo;
+use crate::protocolo::{self, EventoProyeccion};
+use crate::{avisos, carrusel, trigger_projection, AppState};
+use chrono::{Local, NaiveTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tauri::{EventTarget, Emitter, Manager, State};
+
+const INTERVALO: Duration = Duration::from_millis(200);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "modo", rename_all = "snake_case")]
+pub enum ModoTemporizador {
+    Regresiva { segundos: u64 },  // "El culto empieza en 05:00"
+    HastaHora { hora: String },   // Hasta una hora del reloj ("HH:MM"); si ya pasó hoy, la de mañana
+    Cronometro,                   // Cuenta hacia arriba (sermón)
+    Reloj,                        // Hora actual
+}
+
+// Qué hacer cuando una cuenta regresiva llega a cero (además del evento `timer-finished`)
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(tag = "accion", rename_all = "snake_case")]
+pub enum AccionFin {
+    #[default]
+    Ninguna,
+    SiguienteDiapositiva,                        // Avanza el carrusel en reproducción
+    SiguienteElemento,                           // El panel pasa a su siguiente elemento, como el atajo
+    Proyectar { payload: serde_json::Value },    // Igual que trigger_projection
+    Aviso { texto: String, duracion: Option<f64> },
+}
+
+pub struct Temporizador {
+    modo: ModoTemporizador,
+    al_terminar: AccionFin,
+    ventanas: Vec<String>,              // Etiquetas de las ventanas suscritas
+    acumulado: Duration,                // Tiempo corrido antes de la última pausa
+    corriendo_desde: Option<Instant>,
+    objetivo: Option<chrono::DateTime<Local>>, // Para `HastaHora`, fijado al iniciar
+    terminado: bool,
+    ultimo: Option<EstadoTemporizador>, // Lo último enviado, para no repetir ticks iguales
+}
+
+// Temporizadores por nombre (en AppState)
+pub type Temporizadores = HashMap<String, Temporizador>;
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct EstadoTemporizador {
+    pub(crate) nombre: String,
+    #[serde(flatten)]
+    pub(crate) modo: ModoTemporizador,
+    pub(crate) texto: String,  // Ya formateado ("05:00", "1:02:33", "10:45")
+    pub(crate) segundos: i64,  // Restantes en las cuentas regresivas, transcurridos en el cronómetro
+    pub(crate) corriendo: bool,
+    pub(crate) terminado: bool,
+    pub(crate) ventanas: Vec<String>,
+}
+
+fn formatear(segundos: i64) -> String {
+    let s = segundos.max(0);
+    if s >= 3600 { format!("{}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60) } else { format!("{:02}:{:02}", s / 60, s % 60) }
+}
+
+fn leer_hora(hora: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(hora.trim(), "%H:%M").map_err(|_| format!("Hora inválida: {} (use HH:MM)", hora))
+}
+
+// Próxima vez que el reloj marque esa hora
+fn proxima(hora: NaiveTime) -> chrono::DateTime<Local> {
+    let ahora = Local::now();
+    let hoy = ahora.date_naive().and_time(hora).and_local_timezone(Local).earliest().unwrap_or(ahora);
+    if hoy > ahora { hoy } else { hoy + TimeDelta::days(1) }
+}
+
+impl Temporizador {
+    fn transcurrido(&self) -> Duration {
+        self.acumulado + self.corriendo_desde.map(|d| d.elapsed()).unwrap_or_default()
+    }
+
+    fn estado(&self, nombre: &str) -> EstadoTemporizador {
+        let corriendo = self.corriendo_desde.is_some();
+        let segundos = match &self.modo {
+            ModoTemporizador::Regresiva { segundos } => (*segundos as f64 - self.transcurrido().as_secs_f64()).ceil() as i64,
+            ModoTemporizador::HastaHora { .. } => match (self.objetivo, self.terminado) {
+                (_, true) => 0,
+                (Some(objetivo), _) => ((objetivo - Local::now()).num_milliseconds() as f64 / 1000.0).ceil() as i64,
+                (None, _) => 0,
+            },
+            ModoTemporizador::Cronometro => self.transcurrido().as_secs() as i64,
+            ModoTemporizador::Reloj => 0,
+        };
+        let texto = match self.modo {
+            ModoTemporizador::Reloj => Local::now().format("%H:%M").to_string(),
+            _ => formatear(segundos),
+        };
+        EstadoTemporizador { nombre: nombre.to_string(), modo: self.modo.clone(), texto, segundos: segundos.max(0), corriendo, terminado: self.terminado, ventanas: self.ventanas.clone() }
+    }
+
+    fn iniciar(&mut self) -> Result<(), String> {
+        if self.corriendo_desde.is_some() { return Ok(()); }
+        if let ModoTemporizador::HastaHora { hora } = &self.modo {
+            self.objetivo = Some(proxima(leer_hora(hora)?));
+        }
+        self.terminado = false;
+        self.corriendo_desde = Some(Instant::now());
+        Ok(())
+    }
+
+    fn pausar(&mut self) -> Result<(), String> {
+        if matches!(self.modo, ModoTemporizador::HastaHora { .. } | ModoTemporizador::Reloj) {
+            return Err("Este temporizador sigue la hora del reloj y no se puede pausar".to_string());
+        }
+        if let Some(desde) = self.corriendo_desde.take() {
+            self.acumulado += desde.elapsed();
+        }
+        Ok(())
+    }
+
+    fn reiniciar(&mut self) {
+        self.acumulado = Duration::ZERO;
+        self.corriendo_desde = None;
+        self.objetivo = None;
+        self.terminado = false;
+    }
+
+    fn llego_a_cero(&self, estado: &EstadoTemporizador) -> bool {
+        self.corriendo_desde.is_some() && estado.segundos <= 0
+            && matches!(self.modo, ModoTemporizador::Regresiva { .. } | ModoTemporizador::HastaHora { .. })
+    }
+}
+
+// --- TICKS ---
+// Solo a la ventana indicada (`emit` llegaría también a las no suscritas), dentro del protocolo
+fn emitir_a(app: &tauri::AppHandle, etiqueta: &str, evento: &EventoProyeccion) {
+    if let Some(ventana) = app.get_webview_window(etiqueta) {
+        protocolo::emitir(&ventana, evento);
+    }
+}
+
+fn enviar_tick(app: &tauri::AppHandle, estado: &EstadoTemporizador) {
+    let evento = EventoProyeccion::Temporizador(estado.clone());
+    for etiqueta in &estado.ventanas {
+        emitir_a(app, etiqueta, &evento);
+    }
+}
+
+fn ejecutar_fin(app: &tauri::AppHandle, nombre: &str, accion: AccionFin) {
+    let _ = app.emit("timer-finished", nombre);
+    let resultado = match accion {
+        AccionFin::Ninguna => Ok(()),
+        AccionFin::SiguienteDiapositiva => carrusel::slideshow_next(app.state()),
+        AccionFin::SiguienteElemento => app.emit_to(EventTarget::webview_window("main"), "shortcut-action", AccionAtajo::SiguienteElemento).map_err(|e| e.to_string()),
+        AccionFin::Proyectar { payload } => trigger_projection(app.clone(), payload, None, app.state()),
+        AccionFin::Aviso { texto, duracion } => avisos::show_alert(app.clone(), texto, duracion, None, None).map(|_| ()),
+    };
+    if let Err(e) = resultado {
+        println!("Temporizador '{}': no se pudo ejecutar la acción final: {}", nombre, e);
+    }
+}
+
+// Hilo único que revisa todos los temporizadores. Se llama una vez al iniciar la aplicación.
+pub fn iniciar(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(INTERVALO);
+        let mut ticks = Vec::new();
+        let mut finales = Vec::new();
+        {
+            let state = app.state::<AppState>();
+            let Ok(mut temporizadores) = state.temporizadores.lock() else { continue };
+            for (nombre, t) in temporizadores.iter_mut() {
+                let mut estado = t.estado(nombre);
+                if t.llego_a_cero(&estado) {
+                    if let Some(desde) = t.corriendo_desde.take() { t.acumulado += desde.elapsed(); }
+                    t.terminado = true;
+                    estado = t.estado(nombre);
+                    // Copia: la acción sigue en el temporizador para la próxima vez que se inicie
+                    finales.push((nombre.clone(), t.al_terminar.clone()));
+                }
+                if t.ultimo.as_ref() != Some(&estado) {
+                    t.ultimo = Some(estado.clone());
+                    ticks.push(estado);
+                }
+            }
+        }
+        for estado in &ticks {
+            enviar_tick(&app, estado);
+        }
+        for (nombre, accion) in finales {
+            ejecutar_fin(&app, &nombre, accion);
+        }
+    });
+}
+
+// Aplica un cambio a un temporizador; el hilo envía el nuevo estado en el siguiente tick
+fn con_temporizador<T>(state: &AppState, nombre: &str, cambio: impl FnOnce(&mut Temporizador) -> Result<T, String>) -> Result<T, String> {
+    let mut temporizadores = state.temporizadores.lock().map_err(|e| e.to_string())?;
+    let t = temporizadores.get_mut(nombre).ok_or_else(|| format!("No existe el temporizador '{}'", nombre))?;
+    cambio(t)
+}
+
+// ==========================================
+// COMANDOS DE TEMPORIZADORES
+// ==========================================
+// Crea o reemplaza un temporizador (queda detenido, salvo el reloj). Sin ventanas se suscribe el panel ("main").
+#[tauri::command]
+pub fn create_timer(app: tauri::AppHandle, nombre: String, modo: ModoTemporizador, al_terminar: Option<AccionFin>, ventanas: Option<Vec<String>>, state: State<AppState>) -> Result<EstadoTemporizador, String> {
+    let nombre = nombre.trim().to_string();
+    if nombre.is_empty() { return Err("El temporizador necesita un nombre".to_string()); }
+    match &modo {
+        ModoTemporizador::Regresiva { segundos } if *segundos == 0 || *segundos > 24 * 3600 => return Err("La cuenta regresiva debe durar entre 1 segundo y 24 horas".to_string()),
+        ModoTemporizador::HastaHora { hora } => { leer_hora(hora)?; }
+        _ => {}
+    }
+    let mut t = Temporizador {
+        modo,
+        al_terminar: al_terminar.unwrap_or_default(),
+        ventanas: ventanas.unwrap_or_else(|| vec!["main".to_string()]),
+        acumulado: Duration::ZERO,
+        corriendo_desde: None,
+        objetivo: None,
+        terminado: false,
+        ultimo: None,
+    };
+    if t.modo == ModoTemporizador::Reloj { t.iniciar()?; }
+    let estado = t.estado(&nombre);
+    let anterior = state.temporizadores.lock().map_err(|e| e.to_string())?.insert(nombre.clone(), t);
+    // Las ventanas que dejaron de estar suscritas quitan el temporizador de pantalla
+    if let Some(anterior) = anterior {
+        for etiqueta in anterior.ventanas.iter().filter(|v| !estado.ventanas.contains(v)) {
+            emitir_a(&app, etiqueta, &EventoProyeccion::TemporizadorQuitado(nombre.clone()));
+        }
+    }
+    Ok(estado)
+}
+
+#[tauri::command]
+pub fn start_timer(nombre: String, state: State<AppState>) -> Result<(), String> {
+    con_temporizador(&state, &nombre, |t| t.iniciar())
+}
+
+#[tauri::command]
+pub fn pause_timer(nombre: String, state: State<AppState>) -> Result<(), String> {
+    con_temporizador(&state, &nombre, |t| t.pausar())
+}
+
+#[tauri::command]
+pub fn reset_timer(nombre: String, state: State<AppState>) -> Result<(), String> {
+    con_temporizador(&state, &nombre, |t| { t.reiniciar(); Ok(()) })
+}
+
+#[tauri::command]
+pub fn delete_timer(app: tauri::AppHandle, nombre: String, state: State<AppState>) -> Result<(), String> {
+    let quitado = state.temporizadores.lock().map_err(|e| e.to_string())?.remove(&nombre);
+    for etiqueta in quitado.map(|t| t.ventanas).unwrap_or_default() {
+        emitir_a(&app, &etiqueta, &EventoProyeccion::TemporizadorQuitado(nombre.clone()));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_timers(state: State<AppState>) -> Result<Vec<EstadoTemporizador>, String> {
+    let temporizadores = state.temporizadores.lock().map_err(|e| e.to_string())?;
+    let mut lista: Vec<_> = temporizadores.iter().map(|(nombre, t)| t.estado(nombre)).collect();
+    lista.sort_by(|a, b| a.nombre.cmp(&b.nombre));
+    Ok(lista)
+}
+
+// `ventana` es la etiqueta: "main", "projector" o "salida-<id>"
+#[tauri::command]
+pub fn subscribe_timer(nombre: String, ventana: String, state: State<AppState>) -> Result<(), String> {
+    con_temporizador(&state, &nombre, |t| {
+        if !t.ventanas.contains(&ventana) { t.ventanas.push(ventana); }
+        t.ultimo = None; // La ventana nueva recibe el estado en el próximo tick
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn unsubscribe_timer(app: tauri::AppHandle, nombre: String, ventana: String, state: State<AppState>) -> Result<(), String> {
+    con_temporizador(&state, &nombre, |t| { t.ventanas.retain(|v| *v != ventana); Ok(()) })?;
+    emitir_a(&app, &ventana, &EventoProyeccion::TemporizadorQuitado(nombre));
+    Ok(())
+}
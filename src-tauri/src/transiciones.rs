@@ -0,0 +1,129 @@
+// ==========================================
+// TRANSICIONES DE PROYECCIÓN (corte, fundido, fundido a negro)
+// ==========================================
+// Cada `update-proyeccion` lleva `transicion: { texto, fondo }`. Los valores por
+// defecto vienen del tema (dentro del estilo) y el elemento puede cambiarlos con
+// su `EstiloParcial`; el carrusel usa el mismo tipo.
+use crate::temas::{Estilo, EstiloParcial};
+use crate::{guardar_config, leer_config, AppState};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+const CONFIG_TRANSICIONES: &str = "transiciones_multimedia";
+pub const DURACION_MAXIMA: f64 = 10.0;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoTransicion {
+    Corte,
+    // Versiones anteriores ofrecían "deslizar", que el proyector nunca implementó
+    #[default]
+    #[serde(alias = "deslizar")]
+    Fundido,      // Fundido cruzado
+    FundidoNegro, // Baja a negro y sube el contenido nuevo
+}
+
+impl TipoTransicion {
+    pub fn como_texto(self) -> &'static str {
+        match self {
+            TipoTransicion::Corte => "corte",
+            TipoTransicion::Fundido => "fundido",
+            TipoTransicion::FundidoNegro => "fundido_negro",
+        }
+    }
+
+    // Lectura de la columna de texto (carruseles)
+    pub fn desde_texto(texto: &str) -> Result<TipoTransicion, String> {
+        match texto {
+            "corte" => Ok(TipoTransicion::Corte),
+            "fundido" | "deslizar" => Ok(TipoTransicion::Fundido),
+            "fundido_negro" => Ok(TipoTransicion::FundidoNegro),
+            otro => Err(format!("Transición no soportada: {}", otro)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Transicion {
+    pub tipo: TipoTransicion,
+    pub duracion: f64, // Segundos (el corte siempre es 0)
+}
+
+impl Transicion {
+    pub const fn nueva(tipo: TipoTransicion, duracion: f64) -> Transicion {
+        Transicion { tipo, duracion }
+    }
+
+    pub fn validar(&self) -> Result<(), String> {
+        if !self.duracion.is_finite() || !(0.0..=DURACION_MAXIMA).contains(&self.duracion) {
+            return Err("La transición debe durar entre 0 y 10 segundos".to_string());
+        }
+        Ok(())
+    }
+
+    // Lo que se envía al proyector: un corte no tiene duración
+    fn normalizada(self) -> Transicion {
+        if self.tipo == TipoTransicion::Corte { Transicion { duracion: 0.0, ..self } } else { self }
+    }
+}
+
+// Por separado para el cambio de texto y el de fondo (imagen, video o multimedia)
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct Transiciones {
+    pub texto: Transicion,
+    pub fondo: Transicion,
+}
+
+impl Default for Transiciones {
+    fn default() -> Self {
+        Transiciones { texto: Transicion::nueva(TipoTransicion::Fundido, 0.3), fondo: Transicion::nueva(TipoTransicion::Fundido, 0.5) }
+    }
+}
+
+impl Transiciones {
+    pub fn iguales(transicion: Transicion) -> Transiciones {
+        let transicion = transicion.normalizada();
+        Transiciones { texto: transicion, fondo: transicion }
+    }
+
+    pub fn validar(&self) -> Result<(), String> {
+        self.texto.validar()?;
+        self.fondo.validar()
+    }
+
+    fn normalizadas(self) -> Transiciones {
+        Transiciones { texto: self.texto.normalizada(), fondo: self.fondo.normalizada() }
+    }
+}
+
+// Transiciones que acompañan al payload: las del estilo resuelto para texto;
+// para imágenes, videos y PDF, las globales de multimedia con el cambio del elemento
+pub fn resolver(state: &AppState, estilo: Option<&Estilo>, item: Option<&EstiloParcial>) -> Transiciones {
+    let transiciones = match (estilo, item.and_then(|i| i.transiciones)) {
+        (Some(estilo), _) => estilo.transiciones,
+        (None, Some(propias)) => propias,
+        (None, None) => state.multimedia_db.lock().ok()
+            .and_then(|conn| leer_config(&conn, CONFIG_TRANSICIONES))
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+    };
+    transiciones.normalizadas()
+}
+
+// ==========================================
+// COMANDOS DE TRANSICIONES
+// ==========================================
+// Transiciones por defecto de imágenes, videos y PDF (el texto las toma de su tema)
+#[tauri::command]
+pub fn get_media_transitions(state: State<AppState>) -> Result<Transiciones, String> {
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    Ok(leer_config(&conn, CONFIG_TRANSICIONES).and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn set_media_transitions(transiciones: Transiciones, state: State<AppState>) -> Result<(), String> {
+    transiciones.validar()?;
+    let conn = state.multimedia_db.lock().map_err(|e| e.to_string())?;
+    guardar_config(&conn, CONFIG_TRANSICIONES, &serde_json::to_string(&transiciones).map_err(|e| e.to_string())?).map_err(|e| e.to_string())
+}
@@ -3,6 +3,7 @@ import { BrowserRouter as Router, Routes, Route, Link, useLocation } from "react
 import { Music, BookOpen, Image as ImageIcon, Video, FileText, Star, MonitorPlay, Search, ChevronLeft, ChevronRight, Settings, Trash2, Palette, X, Plus, Edit2, AlertTriangle, Type, Maximize, Minimize, Play, Pause, RotateCcw, Clapperboard } from "lucide-react";
 import { invoke, convertFileSrc } from "@tauri-apps/api/core";
 import { emit, listen } from "@tauri-apps/api/event";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 
 
 // --- UTILIDADES ---
@@ -21,6 +22,187 @@ const isSameVerse = (v1: any, v2: any) => {
     return v1.libro === v2.libro && v1.capitulo === v2.capitulo && v1.versiculo === v2.versiculo;
 };
 
+// Versión de protocolo.rs que entiende esta vista
+const PROTOCOLO_PROYECCION = 1;
+
+// Eventos del protocolo de proyección dirigidos a esta ventana: llegan como { protocolo, evento, datos }
+const escucharProtocolo = (evento: string, manejar: (datos: any) => void) => getCurrentWebviewWindow().listen(evento, (e: any) => {
+    if (e.payload?.protocolo > PROTOCOLO_PROYECCION) { console.warn(`Protocolo ${e.payload.protocolo} no soportado en ${evento}`); return; }
+    manejar(e.payload?.datos ?? null);
+});
+
+// Lo que se ve de fondo: si no cambia, la transición solo anima el texto
+const claveFondo = (v: any) => {
+    if (!v) return '';
+    if (v.tipo === 'imagen' || v.tipo === 'video') return `${v.tipo}:${v.ruta}`;
+    if (v.tipo === 'pdf') return `pdf:${v.ruta}/${v.pagina}`;
+    if (v.tipo === 'limpiar') return 'limpiar';
+    return `texto:${v.estilo?.bgColor}|${v.estilo?.bgImage}|${v.estilo?.bgVideo}`;
+};
+
+// Copia fija de un elemento del proyector; los videos se reemplazan por su fotograma actual
+const congelar = (el: HTMLElement): HTMLElement => {
+    const aLienzo = (video: HTMLVideoElement) => {
+        const lienzo = document.createElement('canvas');
+        lienzo.width = video.videoWidth || 1;
+        lienzo.height = video.videoHeight || 1;
+        if (video.videoWidth) lienzo.getContext('2d')?.drawImage(video, 0, 0);
+        lienzo.className = video.className;
+        return lienzo;
+    };
+    const copia = el instanceof HTMLVideoElement ? aLienzo(el) : el.cloneNode(true) as HTMLElement;
+    const originales = el.querySelectorAll('video');
+    copia.querySelectorAll('video').forEach((v, i) => v.replaceWith(aLienzo(originales[i])));
+    copia.style.transition = 'none';
+    return copia;
+};
+
+// ==========================================
+// REPRODUCTOR DE AUDIO (vive en el proyector)
+// ==========================================
+// Atiende "audio-control" de audio.rs y sigue sonando aunque cambien las diapositivas.
+// Dos elementos <audio> se alternan para poder hacer fundido entre pistas.
+const ReproductorAudio = () => {
+  const audioA = useRef<HTMLAudioElement>(null);
+  const audioB = useRef<HTMLAudioElement>(null);
+
+  useEffect(() => {
+    const a = audioA.current!, b = audioB.current!;
+    let pistas: any[] = [];
+    let orden: number[] = [];
+    let indice = 0;
+    let repetir = false;
+    let crossfade = 0;
+    let volumen = 1;
+    let activo = a;
+    let avanzando = false; // Ya se lanzó el paso a la siguiente pista
+    let fundidos: number[] = [];
+
+    const inactivo = () => (activo === a ? b : a);
+    const cancelarFundidos = () => { fundidos.forEach(f => clearInterval(f)); fundidos = []; };
+    // Lleva el volumen de un elemento hasta `destino` en `segundos`
+    const fundir = (el: HTMLAudioElement, destino: number, segundos: number, alTerminar?: () => void) => {
+      const desde = el.volume;
+      const pasos = Math.max(1, Math.round(segundos * 20));
+      let paso = 0;
+      const id = window.setInterval(() => {
+        paso++;
+        el.volume = Math.min(1, Math.max(0, desde + (destino - desde) * paso / pasos));
+        if (paso >= pasos) {
+          clearInterval(id);
+          fundidos = fundidos.filter(f => f !== id);
+          alTerminar?.();
+        }
+      }, 50);
+      fundidos.push(id);
+    };
+    const detener = () => {
+      cancelarFundidos();
+      avanzando = false;
+      [a, b].forEach(el => { el.pause(); if (el.src) el.currentTime = 0; });
+    };
+    // Pasa a la posición `i` de la lista; con fundido la pista actual baja mientras entra la nueva
+    const irA = (i: number) => {
+      if (!orden.length) return;
+      if (i < 0 || i >= orden.length) {
+        if (!repetir) { if (i >= orden.length) detener(); return; }
+        i = (i + orden.length) % orden.length;
+      }
+      indice = i;
+      avanzando = false;
+      cancelarFundidos();
+      const saliente = activo;
+      const entrante = inactivo();
+      entrante.src = convertFileSrc(pistas[orden[i]].ruta);
+      activo = entrante;
+      if (crossfade > 0 && !saliente.paused) {
+        entrante.volume = 0;
+        entrante.play().catch(() => {});
+        fundir(entrante, volumen, crossfade);
+        fundir(saliente, 0, crossfade, () => saliente.pause());
+      } else {
+        saliente.pause();
+        entrante.volume = volumen;
+        entrante.play().catch(() => {});
+      }
+    };
+
+    // Con fundido se empieza la siguiente antes de que termine la actual
+    const alAvanzar = (e: Event) => {
+      const el = e.target as HTMLAudioElement;
+      if (el !== activo || avanzando || crossfade <= 0 || !isFinite(el.duration)) return;
+      if (el.duration - el.currentTime <= crossfade) { avanzando = true; irA(indice + 1); }
+    };
+    const alTerminar = (e: Event) => {
+      if (e.target !== activo || avanzando) return;
+      irA(indice + 1);
+    };
+    [a, b].forEach(el => { el.addEventListener('timeupdate', alAvanzar); el.addEventListener('ended', alTerminar); });
+
+    const un = escucharProtocolo('audio-control', (control: any) => {
+      switch (control?.accion) {
+        case 'load': {
+          detener();
+          pistas = control.pistas || [];
+          orden = pistas.map((_, i) => i);
+          if (control.aleatorio) {
+            for (let i = orden.length - 1; i > 0; i--) {
+              const j = Math.floor(Math.random() * (i + 1));
+              [orden[i], orden[j]] = [orden[j], orden[i]];
+            }
+          }
+          repetir = control.repetir;
+          crossfade = control.crossfade || 0;
+          indice = 0;
+          if (orden.length) activo.src = convertFileSrc(pistas[orden[0]].ruta);
+          break;
+        }
+        case 'play':
+          if (!activo.src) return;
+          cancelarFundidos();
+          activo.volume = volumen;
+          activo.play().catch(() => {});
+          break;
+        case 'pause':
+          cancelarFundidos();
+          [a, b].forEach(el => el.pause());
+          break;
+        case 'stop': detener(); break;
+        case 'next': irA(indice + 1); break;
+        case 'prev': irA(indice - 1); break;
+        case 'seek':
+          if (activo.src) { activo.currentTime = control.segundos; avanzando = false; }
+          break;
+        case 'volume':
+          volumen = control.nivel;
+          cancelarFundidos();
+          activo.volume = volumen;
+          inactivo().pause();
+          break;
+        case 'mute': [a, b].forEach(el => { el.muted = control.activo; }); break;
+        case 'fade_out':
+          cancelarFundidos();
+          inactivo().pause();
+          fundir(activo, 0, control.segundos, detener);
+          break;
+      }
+    });
+
+    return () => {
+      un.then(f => f());
+      detener();
+      [a, b].forEach(el => { el.removeEventListener('timeupdate', alAvanzar); el.removeEventListener('ended', alTerminar); });
+    };
+  }, []);
+
+  return (
+    <>
+      <audio ref={audioA} preload="auto" className="hidden" />
+      <audio ref={audioB} preload="auto" className="hidden" />
+    </>
+  );
+};
+
 // ==========================================
 // 1. VISTA DEL PROYECTOR 
 // ==========================================
@@ -29,6 +211,16 @@ const ProjectorView = () => {
   const [displayVerse, setDisplayVerse] = useState<any>(null);
   const [fontSize, setFontSize] = useState(100);
   const [opacity, setOpacity] = useState(0);
+  // Cada capa anima su opacidad con la duración de su propia transición (texto o fondo)
+  const [textoMs, setTextoMs] = useState(0);
+  const [fondoOpacity, setFondoOpacity] = useState(1);
+  const [fondoMs, setFondoMs] = useState(0);
+  // Copia de lo anterior que se desvanece encima de lo nuevo en el fundido cruzado
+  const [saliente, setSaliente] = useState<{ id: number, nodo: HTMLElement, ms: number } | null>(null);
+  const entradaTextoMs = useRef(0);
+  const displayRef = useRef<any>(null);
+  const marcoRef = useRef<HTMLDivElement>(null);
+  const textoCapaRef = useRef<HTMLDivElement>(null);
   
   const containerRef = useRef<HTMLDivElement>(null);
   const textRef = useRef<HTMLParagraphElement>(null);
@@ -42,40 +234,102 @@ const ProjectorView = () => {
 
   // CORRECCIÓN 1: El estado de los márgenes debe ir AQUÍ AFUERA, no dentro del useEffect
   const [margins, setMargins] = useState({ top: 0, right: 0, bottom: 0, left: 0 });
+  // Aviso superpuesto (cuna, emergencias, anuncios). Lo maneja el backend, independiente de la proyección
+  const [aviso, setAviso] = useState<any>(null);
+  // Temporizadores a los que está suscrita esta ventana (el backend lleva la cuenta)
+  const [timers, setTimers] = useState<Record<string, any>>({});
+  // Negro / sin texto / logo / congelado (congelado lo resuelve el backend dejando de enviar)
+  const [pantalla, setPantalla] = useState<any>({ modo: 'normal', fundido_ms: 500, logo: null });
 
   useEffect(() => {
-    const un1 = listen("update-proyeccion", (e: any) => setLiveVerse(e.payload));
-    const un2 = listen("update-styles", (e: any) => setStyles(e.payload));
+    // Escucha solo lo dirigido a esta ventana: el proyector y cada salida reciben su propia versión
+    const ventana = getCurrentWebviewWindow();
+    const un1 = escucharProtocolo("update-proyeccion", setLiveVerse);
+    const un2 = escucharProtocolo("update-styles", setStyles);
     
-    const un3 = listen("video-control", (e: any) => {
-        if (!videoRef.current) return;
-        const action = e.payload;
-        if (action === 'play') videoRef.current.play();
-        if (action === 'pause') videoRef.current.pause();
-        if (action === 'restart') {
-            videoRef.current.currentTime = 0;
-            videoRef.current.play();
+    const un3 = escucharProtocolo("video-control", (control: any) => {
+        const video = videoRef.current;
+        if (!video) return;
+        switch (control.accion) {
+            case 'play': video.play(); break;
+            case 'pause': video.pause(); break;
+            case 'restart': video.currentTime = 0; video.play(); break;
+            case 'seek': video.currentTime = control.segundos; break;
+            case 'volume': video.volume = control.nivel; break;
+            case 'mute': video.muted = control.activo; break;
+            case 'loop': video.loop = control.activo; break;
         }
     });
 
-    const un4 = listen("update-margins", (e: any) => setMargins(e.payload));
+    const un4 = ventana.listen("update-margins", (e: any) => setMargins(e.payload));
+    // El backend recalcula el estilo de lo que está en pantalla cuando cambian los estilos base
+    const un5 = escucharProtocolo("update-estilo-proyeccion", (estilo: any) => setDisplayVerse((v: any) => v ? { ...v, estilo } : v));
+
+    const un6 = escucharProtocolo("update-aviso", setAviso);
+    const un7 = escucharProtocolo("timer-tick", (estado: any) => setTimers(t => ({ ...t, [estado.nombre]: estado })));
+    const un9 = escucharProtocolo("update-pantalla", setPantalla);
+    const un8 = escucharProtocolo("timer-removed", (nombre: string) => setTimers(t => { const { [nombre]: _, ...resto } = t; return resto; }));
 
-    return () => { un1.then(f => f()); un2.then(f => f()); un3.then(f => f()); un4.then(f => f()); };
+    return () => { un1.then(f => f()); un2.then(f => f()); un3.then(f => f()); un4.then(f => f()); un5.then(f => f()); un6.then(f => f()); un7.then(f => f()); un8.then(f => f()); un9.then(f => f()); };
   }, []);
 
+  useEffect(() => { displayRef.current = displayVerse; }, [displayVerse]);
+
   useEffect(() => {
     if (!liveVerse) return;
-    setOpacity(0);
-    // OPTIMIZACIÓN: Reducimos el tiempo de transición para que se sienta más responsivo
-    const timeout = setTimeout(() => { setDisplayVerse(liveVerse); }, 150);
+    // Las transiciones vienen del backend (tema o elemento): `texto` anima el texto y `fondo`
+    // el fondo del tema o el multimedia. Si cambia el fondo, su transición manda sobre todo el cuadro.
+    // Fundido: una copia fija de lo anterior se desvanece sobre lo nuevo.
+    // Fundido a negro: baja a negro en la mitad del tiempo y lo nuevo sube en la otra mitad.
+    const previo = displayRef.current;
+    const tt = liveVerse.transicion?.texto ?? { tipo: 'fundido', duracion: 0.3 };
+    const tf = liveVerse.transicion?.fondo ?? { tipo: 'fundido', duracion: 0.5 };
+    const cambiaFondo = claveFondo(previo) !== claveFondo(liveVerse);
+    const salida = cambiaFondo ? tf : tt;
+    const salidaMs = salida.duracion * 1000;
+    const previoEsTexto = previo && !['imagen', 'video', 'pdf', 'limpiar'].includes(previo.tipo);
+
+    let espera = 0;
+    if (salida.tipo === 'fundido' && previo && salidaMs > 0) {
+        const copia = document.createElement('div');
+        copia.className = 'absolute inset-0 w-full h-full';
+        if (cambiaFondo) {
+            marcoRef.current?.querySelectorAll<HTMLElement>('[data-fondo]').forEach(el => {
+                if (getComputedStyle(el).visibility !== 'hidden' && el.style.opacity !== '0') copia.appendChild(congelar(el));
+            });
+        }
+        if (previoEsTexto && textoCapaRef.current) {
+            copia.style.color = getComputedStyle(textoCapaRef.current).color;
+            copia.appendChild(congelar(textoCapaRef.current));
+        }
+        setSaliente({ id: Date.now(), nodo: copia, ms: salidaMs });
+    } else if (salida.tipo === 'fundido_negro') {
+        espera = salidaMs / 2;
+        setTextoMs(espera);
+        setOpacity(0);
+        if (cambiaFondo) { setFondoMs(espera); setFondoOpacity(0); }
+    }
+
+    const cambiar = () => {
+        setFondoMs(cambiaFondo && tf.tipo === 'fundido_negro' ? tf.duracion * 500 : 0);
+        setFondoOpacity(1);
+        entradaTextoMs.current = tt.tipo === 'fundido_negro' ? tt.duracion * 500 : tt.duracion * 1000;
+        if (entradaTextoMs.current > 0) { setTextoMs(0); setOpacity(0); }
+        setDisplayVerse(liveVerse);
+    };
+    if (espera === 0) { cambiar(); return; }
+    const timeout = setTimeout(cambiar, espera);
     return () => clearTimeout(timeout);
   }, [liveVerse]);
 
+  // El texto sube con la duración de entrada una vez medido
+  const mostrarTexto = () => { setTextoMs(entradaTextoMs.current); setOpacity(1); };
+
   // OPTIMIZACIÓN DEL WHILE LOOP: Búsqueda Binaria más rápida y segura
   useLayoutEffect(() => {
     if (!displayVerse || displayVerse.tipo === 'imagen' || displayVerse.tipo === 'video' || displayVerse.tipo === 'pdf' || !containerRef.current || !textRef.current) {
         if (displayVerse?.tipo === 'imagen' || displayVerse?.tipo === 'video' || displayVerse?.tipo === 'pdf') {
-            requestAnimationFrame(() => setOpacity(1));
+            requestAnimationFrame(mostrarTexto);
         }
         return;
     }
@@ -85,7 +339,7 @@ const ProjectorView = () => {
     
     // Evitamos cálculos si el texto está vacío
     if (!displayVerse.texto || displayVerse.texto.trim() === "") {
-        setOpacity(1);
+        mostrarTexto();
         return;
     }
 
@@ -103,18 +357,34 @@ const ProjectorView = () => {
     const finalSize = Math.max(20, best - 2);
     setFontSize(finalSize);
     text.style.fontSize = `${finalSize}px`;
-    requestAnimationFrame(() => setOpacity(1));
+    requestAnimationFrame(mostrarTexto);
   }, [displayVerse]);
 
   // Variables de control de visualización
   const isCanto = displayVerse?.capitulo === 0;
-  const currentStyle = isCanto ? styles.cantos : styles.biblia;
+  const currentStyle = displayVerse?.estilo || (isCanto ? styles.cantos : styles.biblia);
   
   const isPdf = displayVerse?.tipo === 'pdf';
   const isVideo = displayVerse?.tipo === 'video';
   const isImage = displayVerse?.tipo === 'imagen';
-  const isText = displayVerse && !isPdf && !isVideo && !isImage;
-
+  const isClear = displayVerse?.tipo === 'limpiar';
+  const isText = displayVerse && !isPdf && !isVideo && !isImage && !isClear;
+
+
+  // Reporta al backend la posición del video para la barra de progreso del panel de control.
+  // El avance de la reproducción (timeupdate, ~4 por segundo) se limita a un reporte por segundo.
+  const ultimoReporte = useRef(0);
+  const reportVideoState = (terminado = false, avance = false) => {
+      const video = videoRef.current;
+      if (!video || !isVideo) return;
+      const ahora = Date.now();
+      if (avance && ahora - ultimoReporte.current < 1000) return;
+      ultimoReporte.current = ahora;
+      invoke("report_video_state", { estado: {
+          ruta: displayVerse.ruta, posicion: video.currentTime, duracion: isFinite(video.duration) ? video.duration : 0,
+          reproduciendo: !video.paused, volumen: video.volume, silencio: video.muted, bucle: video.loop, terminado
+      } });
+  };
 
   // Calculamos la clase de ajuste para imágenes
   const imgFitClass = displayVerse?.aspecto === 'cover' ? 'object-cover' : displayVerse?.aspecto === 'fill' ? 'object-fill' : 'object-contain'; 
@@ -130,25 +400,27 @@ const ProjectorView = () => {
   // OPTIMIZACIÓN RENDER: Todos los elementos existen en el DOM, solo se ocultan.
   return (
     <div className="h-screen w-screen bg-black overflow-hidden relative select-none">
+      <ReproductorAudio />
         
       {/* WRAPPER DE ÁREA SEGURA PARA EL TELÓN */}
       <div 
+        ref={marcoRef}
         className="absolute transition-all duration-300 flex flex-col justify-center items-center overflow-hidden"
         style={{ 
           top: `${margins.top}%`, 
           bottom: `${margins.bottom}%`, 
           left: `${margins.left}%`, 
           right: `${margins.right}%`,
-          backgroundColor: isText ? (currentStyle?.bgColor || '#000') : '#000', 
+          backgroundColor: '#000', 
           color: currentStyle?.textColor || '#fff' 
         }}
       >
       
         {/* 1. CAPA PDF (Ahora renderizada como imagen estática pre-procesada) */}
-        <div className={`absolute inset-0 w-full h-full pointer-events-none transition-opacity duration-300 z-50 flex items-center justify-center ${isPdf ? 'opacity-100' : 'opacity-0'}`} style={{ visibility: isPdf ? 'visible' : 'hidden' }}>
+        <div data-fondo className="absolute inset-0 w-full h-full pointer-events-none z-50 flex items-center justify-center" style={{ visibility: isPdf ? 'visible' : 'hidden', opacity: isPdf ? fondoOpacity : 0, transition: `opacity ${fondoMs}ms ease-in-out` }}>
         {isPdf && displayVerse?.ruta && (
             <img 
-                src={convertFileSrc(`${displayVerse.ruta}/${displayVerse.pagina}.jpg`)} 
+                src={convertFileSrc(`${displayVerse.ruta}/${displayVerse.pagina}.${displayVerse.formato || 'jpg'}`)} 
                 className="w-full h-full object-contain" 
                 alt="PDF Slide"
             />
@@ -158,42 +430,53 @@ const ProjectorView = () => {
         {/* 2. CAPA VIDEO MULTIMEDIA */}
         <video 
             ref={videoRef}
-            className={`absolute inset-0 w-full h-full object-contain transition-opacity duration-300 z-40 ${isVideo ? 'opacity-100' : 'opacity-0'}`}
+            data-fondo
+            className="absolute inset-0 w-full h-full object-contain z-40"
             src={isVideo ? convertFileSrc(displayVerse.ruta) : ''} 
             autoPlay={isVideo} 
             loop={displayVerse?.bucle}
             preload="auto" // Pre-buffering para el disco HDD
-            style={{ visibility: isVideo ? 'visible' : 'hidden' }}
+            style={{ visibility: isVideo ? 'visible' : 'hidden', opacity: isVideo ? fondoOpacity : 0, transition: `opacity ${fondoMs}ms ease-in-out` }}
+            onTimeUpdate={() => reportVideoState(false, true)}
+            onPlay={() => reportVideoState()}
+            onPause={() => reportVideoState()}
+            onVolumeChange={() => reportVideoState()}
+            onEnded={() => reportVideoState(true)}
         />
 
         {/* 3. CAPA IMAGEN MULTIMEDIA */}
         <img 
-            className={`absolute inset-0 w-full h-full transition-opacity duration-300 z-30 ${imgFitClass} ${isImage ? 'opacity-100' : 'opacity-0'}`}
+            data-fondo
+            className={`absolute inset-0 w-full h-full z-30 ${imgFitClass}`}
             src={isImage ? convertFileSrc(displayVerse.ruta) : ''}
-            style={{ visibility: isImage ? 'visible' : 'hidden' }}
+            style={{ visibility: isImage ? 'visible' : 'hidden', opacity: isImage ? fondoOpacity : 0, transition: `opacity ${fondoMs}ms ease-in-out` }}
         />
 
         {/* 4. CAPAS DE TEXTO Y FONDOS */}
-        <div className={`absolute inset-0 w-full h-full transition-opacity duration-300 z-20 ${isText ? 'opacity-100' : 'opacity-0'}`} style={{ opacity: isText ? opacity : 0, visibility: isText ? 'visible' : 'hidden' }}>
-            
-            {/* Fondo de Texto: Imagen */}
-            {currentStyle?.bgImage && (
-                <img src={convertFileSrc(currentStyle.bgImage)} className="absolute inset-0 w-full h-full object-cover z-0" />
-            )}
-            
-            {/* Fondo de Texto: Video */}
-            {currentStyle?.bgVideo && (
-                <video ref={bgVideoRef} src={convertFileSrc(currentStyle.bgVideo)} autoPlay loop muted preload="auto" className="absolute inset-0 w-full h-full object-cover z-0" />
-            )}
+        <div className="absolute inset-0 w-full h-full z-20" style={{ visibility: isText ? 'visible' : 'hidden' }}>
             
-            {/* OPTIMIZACIÓN: Si hay video de fondo, ponemos una capa oscura semi-transparente para mejorar el rendimiento de la sombra del texto */}
-            {currentStyle?.bgVideo && (
-                <div className="absolute inset-0 w-full h-full bg-black/30 z-0"></div>
-            )}
+            {/* Fondo de Texto: color, imagen o video (anima con la transición de fondo) */}
+            <div data-fondo className="absolute inset-0 w-full h-full z-0"
+                 style={{ backgroundColor: currentStyle?.bgColor || '#000', opacity: fondoOpacity, transition: `opacity ${fondoMs}ms ease-in-out` }}>
+                {currentStyle?.bgImage && (
+                    <img src={convertFileSrc(currentStyle.bgImage)} className="absolute inset-0 w-full h-full object-cover" />
+                )}
+                
+                {currentStyle?.bgVideo && (
+                    <video ref={bgVideoRef} src={convertFileSrc(currentStyle.bgVideo)} autoPlay loop muted preload="auto" className="absolute inset-0 w-full h-full object-cover" />
+                )}
+                
+                {/* OPTIMIZACIÓN: Si hay video de fondo, ponemos una capa oscura semi-transparente para mejorar el rendimiento de la sombra del texto */}
+                {currentStyle?.bgVideo && (
+                    <div className="absolute inset-0 w-full h-full bg-black/30"></div>
+                )}
+            </div>
 
-            {/* Contenedor del Texto */}
-            <div className="w-full h-full flex flex-col justify-between px-8 py-8 relative z-10">
-              <div ref={containerRef} className="flex-1 w-full flex items-center justify-center min-h-0 min-w-0">
+            {/* Contenedor del Texto (anima con la transición de texto) */}
+            <div ref={textoCapaRef} className="w-full h-full flex flex-col justify-between px-8 py-8 relative z-10"
+                 style={{ opacity, transition: `opacity ${textoMs}ms ease-in-out` }}>
+              <div ref={containerRef} className="flex-1 w-full flex items-center justify-center min-h-0 min-w-0"
+                   style={{ opacity: pantalla.modo === 'sin_texto' ? 0 : 1, transition: `opacity ${pantalla.fundido_ms}ms ease-in-out` }}>
                 <p ref={textRef} 
                   style={{ fontSize: `${fontSize}px`, lineHeight: 1.25 }} 
                   className={`font-bold text-center font-sans w-full max-w-full break-words whitespace-pre-line ${currentStyle?.bgVideo ? 'text-shadow-strong' : 'drop-shadow-md'}`}>
@@ -212,6 +495,45 @@ const ProjectorView = () => {
             </div>
         </div>
 
+        {/* COPIA DE LO ANTERIOR durante el fundido cruzado (bajo negro, logo y avisos) */}
+        {saliente && (
+            <div key={saliente.id}
+                 ref={el => { if (el && !el.firstChild) el.appendChild(saliente.nodo); }}
+                 className="absolute inset-0 w-full h-full z-[55] pointer-events-none"
+                 style={{ animation: `proyeccion-salida ${saliente.ms}ms ease-in-out forwards` }}
+                 onAnimationEnd={() => setSaliente(s => s?.id === saliente.id ? null : s)} />
+        )}
+
+        {/* 5. NEGRO Y LOGO (avisos y temporizadores quedan por encima) */}
+        <div className="absolute inset-0 w-full h-full bg-black z-[58] flex items-center justify-center pointer-events-none"
+             style={{ opacity: pantalla.modo === 'negro' || pantalla.modo === 'logo' ? 1 : 0, transition: `opacity ${pantalla.fundido_ms}ms ease-in-out` }}>
+            {pantalla.logo && <img src={convertFileSrc(pantalla.logo)} className="max-w-[60%] max-h-[60%] object-contain" />}
+        </div>
+
+        {/* 6. TEMPORIZADORES */}
+        {Object.keys(timers).length > 0 && (
+            <div className="absolute top-6 right-8 z-[60] flex flex-col items-end gap-2">
+                {Object.values(timers).map((t: any) => (
+                    <p key={t.nombre} className={`text-6xl font-black tabular-nums text-shadow-strong ${t.terminado ? 'text-red-500' : 'text-white'}`}>{t.texto}</p>
+                ))}
+            </div>
+        )}
+
+        {/* 7. CAPA DE AVISOS */}
+        {aviso && (
+            <div key={aviso.id} className={`absolute left-0 right-0 z-[60] overflow-hidden px-8 py-4 shadow-2xl ${aviso.estilo.posicion === 'arriba' ? 'top-0' : 'bottom-0'}`}
+                 style={{ backgroundColor: aviso.estilo.fondo, color: aviso.estilo.color }}>
+                {aviso.estilo.modo === 'cinta' ? (
+                    <p className="aviso-cinta text-5xl font-black whitespace-nowrap"
+                       style={{ animationDuration: `${(window.innerWidth + aviso.texto.length * 40) / aviso.estilo.velocidad}s` }}>
+                        {aviso.texto}
+                    </p>
+                ) : (
+                    <p className="text-5xl font-black text-center">{aviso.texto}</p>
+                )}
+            </div>
+        )}
+
       {/* CORRECCIÓN 2: Este era el div de cierre que faltaba y nos rompía todo */}
       </div> 
     </div>
@@ -981,12 +1303,21 @@ const DashboardLayout = () => {
   const [syncMessage, setSyncMessage] = useState("");
   
   // Agregamos bucle a activeBookInfo
-  const [activeBookInfo, setActiveBookInfo] = useState({ book: "", cap: 0, cantoId: null as number | null, tipo: 'texto', ruta: '', imgId: null as number | null, aspecto: 'contain', bucle: false });
+  const [activeBookInfo, setActiveBookInfo] = useState<{ book: string, cap: number, cantoId: number | null, tipo: string, ruta: string, imgId: number | null, aspecto: string, bucle: boolean, formato?: string }>({ book: "", cap: 0, cantoId: null, tipo: 'texto', ruta: '', imgId: null, aspecto: 'contain', bucle: false });
   const [previewVerse, setPreviewVerse] = useState<any>(null);
   const [favorites, setFavorites] = useState<any[]>([]);
+  const [favoritoActual, setFavoritoActual] = useState(-1); // Último favorito proyectado
   const [pdfPage, setPdfPage] = useState(1);
   const scrollRef = useRef<HTMLDivElement>(null); 
 
+  // Estado del video en el proyector (posición, duración, fin)
+  const [videoState, setVideoState] = useState<any>(null);
+  useEffect(() => {
+      const unlisten = getCurrentWebviewWindow().listen("video-state", (e: any) => setVideoState(e.payload));
+      return () => { unlisten.then(f => f()); };
+  }, []);
+  const formatTime = (s: number) => `${Math.floor(s / 60)}:${Math.floor(s % 60).toString().padStart(2, '0')}`;
+
   // ---> NUEVO: Estado para almacenar el PDF binario
   const [_pdfBlob, _setPdfBlob] = useState<Blob | null>(null);
   
@@ -998,7 +1329,11 @@ const DashboardLayout = () => {
   // Estilos de fondo actualizados para contener video
   const [bibleStyles, setBibleStyles] = useState({ bgColor: '#000000', textColor: '#ffffff', bgImage: '', bgVideo: '' });
   const [cantoStyles, setCantoStyles] = useState({ bgColor: '#000000', textColor: '#ffffff', bgImage: '', bgVideo: '' });
-  
+  // Los estilos se guardan en el backend: los recuperamos al iniciar
+  useEffect(() => {
+      invoke("get_projector_styles").then((s: any) => { setBibleStyles(s.biblia); setCantoStyles(s.cantos); });
+  }, []);
+
   const [styleTab, setStyleTab] = useState<'biblia' | 'cantos'>('biblia');
   const [showStyleModal, setShowStyleModal] = useState(false);
   const [recentImages, setRecentImages] = useState<string[]>([]);
@@ -1018,15 +1353,37 @@ const DashboardLayout = () => {
         const index = currentChapter.findIndex(v => isSameVerse(v, previewVerse));
         if (index !== -1) document.getElementById(`verse-${index}`)?.scrollIntoView({ behavior: 'smooth', block: 'start' });
     }
-    const handleKey = (e: KeyboardEvent) => {
-        if (document.activeElement?.tagName === 'INPUT' || document.activeElement?.tagName === 'TEXTAREA' || !currentChapter.length || !previewVerse || previewVerse.tipo === 'imagen' || previewVerse.tipo === 'video') return;
+    const mover = (paso: number) => {
+        if (!currentChapter.length || !previewVerse || previewVerse.tipo === 'imagen' || previewVerse.tipo === 'video') return;
         const idx = currentChapter.findIndex(v => isSameVerse(v, previewVerse));
-        if (e.key === 'ArrowDown' && currentChapter[idx + 1]) projectVerse(currentChapter[idx + 1]);
-        if (e.key === 'ArrowUp' && currentChapter[idx - 1]) projectVerse(currentChapter[idx - 1]);
+        if (currentChapter[idx + paso]) projectVerse(currentChapter[idx + paso]);
+    };
+    const handleKey = (e: KeyboardEvent) => {
+        if (document.activeElement?.tagName === 'INPUT' || document.activeElement?.tagName === 'TEXTAREA') return;
+        if (e.key === 'ArrowDown') mover(1);
+        if (e.key === 'ArrowUp') mover(-1);
     };
     window.addEventListener('keydown', handleKey);
-    return () => window.removeEventListener('keydown', handleKey);
-  }, [previewVerse, currentChapter]);
+    // Siguiente de la lista de favoritos, que hace de orden del culto. Si lo que está en
+    // pantalla no es un favorito (ej: un video intercalado) sigue desde el último usado.
+    const siguienteFavorito = () => {
+        const actual = favorites.findIndex((f: any) => f.isCanto ? f.cantoId === activeBookInfo.cantoId : isSameVerse(f, previewVerse));
+        const siguiente = favorites[(actual !== -1 ? actual : favoritoActual) + 1];
+        if (siguiente) handleFavoriteAction(siguiente);
+    };
+    const ventana = getCurrentWebviewWindow();
+    // Atajos globales (control remoto de presentaciones) que Rust no resolvió con el carrusel
+    const unlisten = ventana.listen("shortcut-action", (e: any) => {
+        if (e.payload === 'siguiente') mover(1);
+        if (e.payload === 'anterior') mover(-1);
+        if (e.payload === 'siguiente_elemento') siguienteFavorito();
+    });
+    // Al terminar el video en pantalla (sin bucle) el culto sigue con el siguiente favorito
+    const unlistenFin = ventana.listen("video-ended", (e: any) => {
+        if (previewVerse?.tipo === 'video' && previewVerse.ruta === e.payload && !previewVerse.bucle) siguienteFavorito();
+    });
+    return () => { window.removeEventListener('keydown', handleKey); unlisten.then(f => f()); unlistenFin.then(f => f()); };
+  }, [previewVerse, currentChapter, favorites, activeBookInfo, favoritoActual]);
 
   const loadChapter = (version: string, book: string, cap: number) => {
     const cacheKey = `${version}-${book}-${cap}`;
@@ -1051,7 +1408,7 @@ const DashboardLayout = () => {
     }
 
     invoke("get_canto_diapositivas", { cantoId: canto.id }).then((slides: any) => {
-      const formattedSlides = slides.map((s: any) => ({ libro: canto.titulo, capitulo: 0, versiculo: s.orden, texto: s.texto, versionName: "CANTO" }));
+      const formattedSlides = slides.map((s: any) => ({ libro: canto.titulo, capitulo: 0, versiculo: s.orden, texto: s.texto, versionName: "CANTO", canto_id: canto.id }));
       setCantoCache(prev => ({ ...prev, [canto.id]: formattedSlides })); 
       setCurrentChapter(formattedSlides);
       setActiveBookInfo({ book: canto.titulo, cap: 0, cantoId: canto.id, tipo: 'texto', ruta: '', imgId: null, aspecto: 'contain', bucle: false }); 
@@ -1073,18 +1430,19 @@ const DashboardLayout = () => {
     setPreviewVerse(vWithVersion);
   };
 
-  const emitVideoControl = (action: string) => {
-      invoke("trigger_video_control", { action });
+  const emitVideoControl = (accion: string, extra: any = {}) => {
+      invoke("trigger_video_control", { action: { accion, ...extra } });
   };
 
   const handleFavoriteAction = (fav: any) => {
+      setFavoritoActual(favorites.indexOf(fav));
       if (fav.isCanto) loadCanto(fav.cantoData);
       else projectVerse(fav); 
   };
 
   const handleCantoUpdated = (id: number, nuevoTitulo: string, nuevaLetra: string) => {
       const estrofas = nuevaLetra.split('\n\n').map(s => s.trim()).filter(s => s !== '');
-      const formattedSlides = estrofas.map((texto, i) => ({ libro: nuevoTitulo, capitulo: 0, versiculo: i + 1, texto: texto, versionName: "CANTO" }));
+      const formattedSlides = estrofas.map((texto, i) => ({ libro: nuevoTitulo, capitulo: 0, versiculo: i + 1, texto: texto, versionName: "CANTO", canto_id: id }));
       setCantoCache(prev => ({ ...prev, [id]: formattedSlides }));
       if (activeBookInfo.cantoId === id) {
           setCurrentChapter(formattedSlides); setActiveBookInfo(prev => ({ ...prev, book: nuevoTitulo })); setPreviewVerse(null); 
@@ -1216,7 +1574,7 @@ const DashboardLayout = () => {
                               <button onClick={() => {
                                   const newPage = Math.max(1, pdfPage - 1);
                                   setPdfPage(newPage);
-                                  projectVerse({ tipo: 'pdf', ruta: activeBookInfo.ruta, pagina: newPage });
+                                  projectVerse({ tipo: 'pdf', ruta: activeBookInfo.ruta, pagina: newPage, formato: activeBookInfo.formato });
                               }} className="text-gray-400 hover:text-white hover:bg-white/10 p-1.5 rounded transition-colors"><ChevronLeft size={16}/></button>
                               
                               <span className="text-[11px] font-black w-24 text-center tracking-widest text-gray-200">PÁG. {pdfPage}</span>
@@ -1224,11 +1582,11 @@ const DashboardLayout = () => {
                               <button onClick={() => {
                                   const newPage = pdfPage + 1;
                                   setPdfPage(newPage);
-                                  projectVerse({ tipo: 'pdf', ruta: activeBookInfo.ruta, pagina: newPage });
+                                  projectVerse({ tipo: 'pdf', ruta: activeBookInfo.ruta, pagina: newPage, formato: activeBookInfo.formato });
                               }} className="text-gray-400 hover:text-white hover:bg-white/10 p-1.5 rounded transition-colors"><ChevronRight size={16}/></button>
                           </div>
                           
-                          <button onClick={() => projectVerse({ tipo: 'pdf', ruta: activeBookInfo.ruta, pagina: pdfPage })} className="bg-accent text-white px-5 py-2 rounded shadow-lg text-xs font-black uppercase flex items-center gap-2 hover:bg-accent/80 transition-all active:scale-95">
+                          <button onClick={() => projectVerse({ tipo: 'pdf', ruta: activeBookInfo.ruta, pagina: pdfPage, formato: activeBookInfo.formato })} className="bg-accent text-white px-5 py-2 rounded shadow-lg text-xs font-black uppercase flex items-center gap-2 hover:bg-accent/80 transition-all active:scale-95">
                               <MonitorPlay size={14}/> Sincronizar
                           </button>
                       </div>
@@ -1236,7 +1594,7 @@ const DashboardLayout = () => {
                       <div className="w-full flex-1 flex justify-center items-center overflow-hidden bg-black/80 relative">
                           {activeBookInfo.ruta ? (
                               <img 
-                                  src={convertFileSrc(`${activeBookInfo.ruta}/${pdfPage}.jpg`)} 
+                                  src={convertFileSrc(`${activeBookInfo.ruta}/${pdfPage}.${activeBookInfo.formato || 'jpg'}`)} 
                                   className="max-h-[450px] shadow-2xl object-contain" 
                                   alt="Vista previa PDF" 
                               />
@@ -1270,6 +1628,19 @@ const DashboardLayout = () => {
                               <button onClick={() => emitVideoControl('pause')} className="flex items-center gap-2 bg-white/5 hover:bg-white/10 text-white px-6 py-3 rounded-xl transition-all active:scale-95 font-bold text-[11px] uppercase border border-white/10"><Pause size={16} className="text-yellow-400"/> Pausar</button>
                               <button onClick={() => emitVideoControl('restart')} className="flex items-center gap-2 bg-white/5 hover:bg-white/10 text-white px-6 py-3 rounded-xl transition-all active:scale-95 font-bold text-[11px] uppercase border border-white/10"><RotateCcw size={16} className="text-blue-400"/> Reiniciar</button>
                           </div>
+                          {videoState && videoState.ruta === activeBookInfo.ruta && videoState.duracion > 0 && (
+                              <div className="w-full mt-5 flex items-center gap-3 text-[10px] font-bold text-gray-400">
+                                  <span>{formatTime(videoState.posicion)}</span>
+                                  <div className="flex-1 h-2 bg-white/10 rounded-full overflow-hidden cursor-pointer"
+                                       onClick={(e) => {
+                                           const rect = e.currentTarget.getBoundingClientRect();
+                                           emitVideoControl('seek', { segundos: ((e.clientX - rect.left) / rect.width) * videoState.duracion });
+                                       }}>
+                                      <div className="bg-accent h-full rounded-full" style={{ width: `${(videoState.posicion / videoState.duracion) * 100}%` }}></div>
+                                  </div>
+                                  <span>{formatTime(videoState.duracion)}</span>
+                              </div>
+                          )}
                       </div>
                   )}
               </div>
@@ -1356,7 +1727,7 @@ const DashboardLayout = () => {
                   <Route path="/pdf" element={<PdfLibrary 
                       onSelectPdf={(doc: any) => { 
                           setPdfPage(1); 
-                          setActiveBookInfo({ book: doc.nombre, cap: 0, cantoId: null, tipo: 'pdf', ruta: doc.ruta, imgId: doc.id, aspecto: 'contain', bucle: false }); 
+                          setActiveBookInfo({ book: doc.nombre, cap: 0, cantoId: null, tipo: 'pdf', ruta: doc.ruta, imgId: doc.id, aspecto: 'contain', bucle: false, formato: doc.opciones?.formato }); 
                           setPreviewVerse(null); 
                           setCurrentChapter([]); 
                       }}